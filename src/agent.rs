@@ -0,0 +1,519 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use error_chain::error_chain;
+use serde_json::{json, Value};
+use snake_in_terminal::terminus::screen::{Screen, SharedScreen};
+
+use crate::{
+    cli::{AgentArgs, Cli},
+    component::{
+        self, border::BorderComponent, game_board::GameBoardComponent, Boundary, Dimension,
+        Position,
+    },
+    game_state::{fruit::FruitRates, snake::Direction, GameEvent, GameState, Player},
+    layout,
+    map::Map,
+    replay,
+    settings::{Opponent, SpeedCurve},
+};
+
+error_chain! {
+    errors {
+        InvalidRequest(message: String) {
+            description("Invalid request from the agent.")
+            display("Invalid request from the agent: {}", message)
+        }
+        BoardTooSmall {
+            description("The board is too small to hold the snake.")
+            display("The board is too small to hold the snake.")
+        }
+    }
+
+    links {
+        Component(component::Error, component::ErrorKind);
+    }
+
+    foreign_links {
+        Io(std::io::Error);
+        Json(serde_json::Error);
+    }
+}
+
+/// Game played by another program, like an environment of reinforcement learning.
+///
+/// Each request read is answered by the observation of the board, the reward of the step,
+/// whether the game is over and the events of the step. Every position is relative to the top
+/// left cell of the board.
+pub struct Agent {
+    state: GameState,
+    speed_curve: SpeedCurve,
+    renderer: Option<Renderer>,
+}
+
+/// Drawing of the game on another terminal than the one of the agent.
+struct Renderer {
+    screen: SharedScreen<File>,
+    game_board: GameBoardComponent<File>,
+    border: BorderComponent<File>,
+}
+
+impl Agent {
+    /// Round set up from the options of the command line, drawn on the tty when there is one.
+    pub fn new(cli: &Cli, args: &AgentArgs, map: Option<Map>) -> Result<Self> {
+        // The border and the title are around the board.
+        let screen_dimension =
+            Dimension::new(args.width.saturating_add(4), args.height.saturating_add(5));
+        let board = Boundary::new(
+            layout::get_game_board_boundary(screen_dimension).position(),
+            Dimension::new(args.width, args.height),
+        );
+        let state = GameState::new(
+            board,
+            cli.seed.unwrap_or_else(rand::random),
+            cli.mode,
+            Opponent::Solo,
+            map.map(Arc::new),
+            FruitRates {
+                golden: cli.golden_rate,
+                shrink: cli.shrink_rate,
+                slow_motion: cli.slow_motion_rate,
+                poison: cli.poison_rate,
+            },
+        );
+        if !state.can_hold_snake() {
+            return Err(ErrorKind::BoardTooSmall.into());
+        }
+        let renderer = args
+            .tty
+            .as_ref()
+            .map(|tty| Renderer::new(tty, screen_dimension, &state))
+            .transpose()?;
+        let mut agent = Self {
+            state,
+            speed_curve: cli.speed_curve,
+            renderer,
+        };
+        agent.render()?;
+        Ok(agent)
+    }
+
+    /// Answer every request of the input until it ends or the agent close the game.
+    ///
+    /// A wrong request is answered with an error, the game goes on.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match self.answer(&line) {
+                Ok(Some(response)) => response,
+                Ok(None) => break,
+                Err(Error(ErrorKind::InvalidRequest(message), _)) => json!({ "error": message }),
+                Err(error) => return Err(error),
+            };
+            writeln!(output, "{response}")?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Response to the request, none when the agent close the game.
+    fn answer(&mut self, line: &str) -> Result<Option<Value>> {
+        let request: Value = serde_json::from_str(line)
+            .map_err(|error| ErrorKind::InvalidRequest(error.to_string()))?;
+        let response = match request["type"].as_str() {
+            Some("reset") => self.reset(&request["seed"])?,
+            Some("step") => self.step(&request["direction"])?,
+            Some("observe") => self.response(0, &[]),
+            Some("close") => return Ok(None),
+            _ => {
+                let message = "the type must be reset, step, observe or close";
+                return Err(ErrorKind::InvalidRequest(message.to_string()).into());
+            }
+        };
+        Ok(Some(response))
+    }
+
+    /// Start a new round with the seed, or with the next seed of the current round.
+    fn reset(&mut self, seed: &Value) -> Result<Value> {
+        if seed.is_null() {
+            self.state.reset();
+        } else {
+            let seed = seed.as_u64().ok_or_else(|| {
+                ErrorKind::InvalidRequest("the seed must be a positive integer".to_string())
+            })?;
+            self.state = GameState::new(
+                self.state.boundary(),
+                seed,
+                self.state.mode(),
+                Opponent::Solo,
+                self.state.map().clone(),
+                self.state.fruit_rates(),
+            );
+        }
+        self.render()?;
+        Ok(self.response(0, &[]))
+    }
+
+    /// Move the snake once, the reward is the score won or lost during the step.
+    fn step(&mut self, direction: &Value) -> Result<Value> {
+        let turns: Vec<(Player, Direction)> = if direction.is_null() {
+            Vec::new()
+        } else {
+            let direction = direction
+                .as_str()
+                .and_then(Direction::from_name)
+                .ok_or_else(|| {
+                    ErrorKind::InvalidRequest(
+                        "the direction must be up, down, left or right".to_string(),
+                    )
+                })?;
+            vec![(Player::One, direction)]
+        };
+        let score = self.state.score();
+        let events = self.state.step(&turns);
+        self.render()?;
+        Ok(self.response(i64::from(self.state.score()) - i64::from(score), &events))
+    }
+
+    fn response(&self, reward: i64, events: &[GameEvent]) -> Value {
+        json!({
+            "observation": self.observation(),
+            "reward": reward,
+            "done": self.state.is_over(),
+            "events": events.iter().map(|event| self.event(event)).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Everything on the board, the snake goes from its head to its tail.
+    fn observation(&self) -> Value {
+        let state = &self.state;
+        let board = state.boundary();
+        let mut walls: Vec<Position> = state.walls().iter().copied().collect();
+        walls.sort_by_key(|wall| (wall.y, wall.x));
+        json!({
+            "tick": state.tick(),
+            "score": state.score(),
+            "width": board.dimension().width,
+            "height": board.dimension().height,
+            "mode": replay::mode_name(state.mode()),
+            "direction": state.snake().head().direction().name(),
+            "snake": state
+                .snake()
+                .nodes()
+                .iter()
+                .map(|node| self.position(node.position()))
+                .collect::<Vec<_>>(),
+            "apple": state.apple().map(|apple| self.position(apple)),
+            "fruit": state.fruit().map(|fruit| json!({
+                "kind": fruit.kind().name(),
+                "position": self.position(fruit.position()),
+                "expires_at": fruit.expires_at(),
+            })),
+            "walls": walls.into_iter().map(|wall| self.position(wall)).collect::<Vec<_>>(),
+            "is_over": state.is_over(),
+            "is_won": state.is_won(),
+            "death_cause": state.death_cause().map(|cause| cause.name()),
+        })
+    }
+
+    fn event(&self, event: &GameEvent) -> Value {
+        match *event {
+            GameEvent::DirectionChanged(_, direction) => {
+                json!({ "type": "direction_changed", "direction": direction.name() })
+            }
+            GameEvent::AppleEaten(_, position) => {
+                json!({ "type": "apple_eaten", "position": self.position(position) })
+            }
+            GameEvent::AppleSpawned(position) => {
+                json!({ "type": "apple_spawned", "position": self.position(position) })
+            }
+            GameEvent::FruitEaten(_, kind, position) => json!({
+                "type": "fruit_eaten", "kind": kind.name(), "position": self.position(position)
+            }),
+            GameEvent::FruitSpawned(kind, position) => json!({
+                "type": "fruit_spawned", "kind": kind.name(), "position": self.position(position)
+            }),
+            GameEvent::FruitVanished(position) => {
+                json!({ "type": "fruit_vanished", "position": self.position(position) })
+            }
+            GameEvent::Moved(_, position) => {
+                json!({ "type": "moved", "position": self.position(position) })
+            }
+            GameEvent::Won => json!({ "type": "won" }),
+            GameEvent::GameOver => json!({ "type": "game_over" }),
+        }
+    }
+
+    /// Position relative to the top left cell of the board.
+    ///
+    /// Outside of the board, like a head leaving it, the position can be negative.
+    fn position(&self, position: Position) -> Value {
+        let origin = self.state.boundary().position();
+        json!([
+            position.x as i32 - origin.x as i32,
+            position.y as i32 - origin.y as i32
+        ])
+    }
+
+    fn render(&mut self) -> Result<()> {
+        if let Some(renderer) = &mut self.renderer {
+            renderer.render(&self.state, self.speed_curve.level(&self.state))?;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer {
+    fn new(tty: &Path, screen_dimension: Dimension, state: &GameState) -> Result<Self> {
+        let tty: File = OpenOptions::new().write(true).open(tty)?;
+        let mut screen = Screen::with_size(
+            || tty.try_clone().expect("The tty is already open."),
+            screen_dimension.width,
+            screen_dimension.height,
+        );
+        screen.clear_screen()?;
+        let screen = SharedScreen::new(screen);
+        Ok(Self {
+            game_board: GameBoardComponent::new(SharedScreen::clone(&screen), state.boundary()),
+            border: layout::create_application_border(
+                SharedScreen::clone(&screen),
+                state.boundary(),
+                state.mode(),
+            )?,
+            screen,
+        })
+    }
+
+    fn render(&mut self, state: &GameState, level: u32) -> component::Result<()> {
+        self.game_board.render(state, level)?;
+        self.border.render()?;
+        self.screen.lock()?.present()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use serde_json::{json, Value};
+
+    use super::Agent;
+    use crate::{
+        cli::{Cli, Command},
+        component::Position,
+    };
+
+    /// Agent set up from the options, like `--seed 1 agent --width 20`.
+    fn agent(options: &[&str]) -> Agent {
+        let cli =
+            Cli::parse_from(std::iter::once("snake-in-terminal").chain(options.iter().copied()));
+        let Some(Command::Agent(args)) = &cli.command else {
+            panic!("The options have no agent subcommand.");
+        };
+        Agent::new(&cli, args, None).unwrap()
+    }
+
+    /// Responses to the requests, one per line.
+    fn run(agent: &mut Agent, requests: &[Value]) -> Vec<Value> {
+        let input: String = requests
+            .iter()
+            .map(|request| format!("{request}\n"))
+            .collect();
+        let mut output = Vec::new();
+        agent.run(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn answer_the_board_relative_to_its_top_left_cell() {
+        let mut agent = agent(&["--seed", "1", "agent", "--width", "20", "--height", "5"]);
+
+        let responses = run(&mut agent, &[json!({ "type": "observe" })]);
+
+        let observation = &responses[0]["observation"];
+        assert_eq!(observation["tick"], 0);
+        assert_eq!(observation["width"], 20);
+        assert_eq!(observation["height"], 5);
+        assert_eq!(observation["direction"], "right");
+        assert_eq!(observation["snake"][0], json!([10, 0]));
+        assert_eq!(observation["snake"][10], json!([0, 0]));
+        assert_eq!(responses[0]["reward"], 0);
+        assert_eq!(responses[0]["done"], false);
+    }
+
+    #[test]
+    fn give_negative_positions_left_and_above_the_board() {
+        let agent = agent(&["agent"]);
+        let origin = agent.state.boundary().position();
+
+        assert_eq!(agent.position(origin), json!([0, 0]));
+        assert_eq!(
+            agent.position(Position::new(0, 0)),
+            json!([-(origin.x as i32), -(origin.y as i32)])
+        );
+    }
+
+    #[test]
+    fn step_straight_without_direction() {
+        let mut agent = agent(&["--seed", "1", "agent", "--width", "20", "--height", "5"]);
+
+        let responses = run(
+            &mut agent,
+            &[
+                json!({ "type": "step" }),
+                json!({ "type": "step", "direction": "down" }),
+            ],
+        );
+
+        assert_eq!(
+            responses[0]["events"],
+            json!([{ "type": "moved", "position": [11, 0] }])
+        );
+        assert_eq!(
+            responses[1]["events"],
+            json!([
+                { "type": "direction_changed", "direction": "down" },
+                { "type": "moved", "position": [11, 1] }
+            ])
+        );
+        assert_eq!(responses[1]["observation"]["tick"], 2);
+    }
+
+    #[test]
+    fn reset_with_the_seed_or_the_next_one() {
+        let mut agent = agent(&["--seed", "1", "agent", "--width", "20", "--height", "5"]);
+
+        let responses = run(
+            &mut agent,
+            &[
+                json!({ "type": "reset", "seed": 7 }),
+                json!({ "type": "step" }),
+                json!({ "type": "reset", "seed": 7 }),
+                json!({ "type": "reset" }),
+            ],
+        );
+
+        assert_eq!(responses[1]["observation"]["tick"], 1);
+        assert_eq!(responses[0]["observation"], responses[2]["observation"]);
+        assert_eq!(responses[3]["observation"]["tick"], 0);
+        assert_eq!(responses[3]["observation"]["snake"][0], json!([10, 0]));
+    }
+
+    #[test]
+    fn answer_an_invalid_request_with_an_error_and_go_on() {
+        let mut agent = agent(&["--seed", "1", "agent"]);
+        let mut output = Vec::new();
+        let input = "not json\n\
+                     {\"type\": \"jump\"}\n\
+                     {\"type\": \"step\", \"direction\": \"north\"}\n\
+                     {\"type\": \"reset\", \"seed\": -1}\n\
+                     {\"type\": \"observe\"}\n";
+
+        agent.run(input.as_bytes(), &mut output).unwrap();
+
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 5);
+        for response in &responses[..4] {
+            assert!(response["error"].is_string(), "{response}");
+        }
+        assert_eq!(responses[4]["observation"]["tick"], 0);
+    }
+
+    #[test]
+    fn stop_answering_once_closed() {
+        let mut agent = agent(&["agent"]);
+
+        let responses = run(
+            &mut agent,
+            &[
+                json!({ "type": "observe" }),
+                json!({ "type": "close" }),
+                json!({ "type": "observe" }),
+            ],
+        );
+
+        assert_eq!(responses.len(), 1);
+    }
+
+    #[test]
+    fn give_a_negative_reward_for_a_poison() {
+        let mut agent = agent(&[
+            "--seed",
+            "3",
+            "--mode",
+            "wrap",
+            "--golden-rate",
+            "0",
+            "--shrink-rate",
+            "0",
+            "--slow-motion-rate",
+            "0",
+            "--poison-rate",
+            "100",
+            "agent",
+            "--width",
+            "20",
+            "--height",
+            "10",
+        ]);
+        let mut response = run(&mut agent, &[json!({ "type": "observe" })]).remove(0);
+
+        for _ in 0..500 {
+            let observation = &response["observation"];
+            assert_eq!(observation["is_over"], false);
+            let target = if observation["fruit"].is_null() {
+                &observation["apple"]
+            } else {
+                &observation["fruit"]["position"]
+            };
+            let score = observation["score"].as_i64().unwrap();
+            let direction = toward(&observation["snake"][0], target, 20, 10);
+            response = run(
+                &mut agent,
+                &[json!({ "type": "step", "direction": direction })],
+            )
+            .remove(0);
+
+            let events = response["events"].as_array().unwrap();
+            if events.iter().any(|event| event["type"] == "fruit_eaten") {
+                let reward = response["reward"].as_i64().unwrap();
+                assert!(reward < 0);
+                assert_eq!(
+                    reward,
+                    response["observation"]["score"].as_i64().unwrap() - score
+                );
+                return;
+            }
+        }
+        panic!("The snake never reached the poison.");
+    }
+
+    /// Direction of the shortest way to the target on a board the snake wrap around,
+    /// across first then down or up.
+    fn toward(head: &Value, target: &Value, width: i64, height: i64) -> &'static str {
+        let distance = |axis: usize, side: i64| {
+            (target[axis].as_i64().unwrap() - head[axis].as_i64().unwrap()).rem_euclid(side)
+        };
+        match (distance(0, width), distance(1, height)) {
+            (0, dy) if dy <= height / 2 => "down",
+            (0, _) => "up",
+            (dx, _) if dx <= width / 2 => "right",
+            _ => "left",
+        }
+    }
+}
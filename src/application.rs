@@ -0,0 +1,558 @@
+use std::{io::Stdout, path::PathBuf, sync::Arc, time::Duration};
+
+use snake_in_terminal::terminus::screen::SharedScreen;
+
+use crate::{
+    cli::{Cli, Command},
+    component::{
+        self,
+        leaderboard::{LeaderboardComponent, LEADERBOARD_WIDTH},
+        menu::{menu_dimension, MenuComponent},
+        title::TitleComponent,
+        Dimension, Position,
+    },
+    computer::Skill,
+    game_input::GameInput,
+    game_state::{fruit::FruitRates, snake::Direction, Player},
+    high_score::{HighScores, RoundSettings},
+    layout,
+    map::Map,
+    map_editor::MapEditor,
+    replay::Replay,
+    settings::{GameMode, Settings},
+    snake_game::SnakeGame,
+};
+
+/// Time without input on the main menu before the demo start.
+pub const IDLE_DELAY: Duration = Duration::from_secs(30);
+
+/// Screens of the program, from the title screen to the game, and the settings they share.
+pub struct Application {
+    screen: SharedScreen<Stdout>,
+    settings: Settings,
+    /// Seed of the next round, a random one is used when it is not set.
+    seed: Option<u64>,
+    record_path: Option<PathBuf>,
+    high_scores_path: Option<PathBuf>,
+    /// Levels that can be chosen in the menu, the built-in ones and the one given to the program.
+    maps: Vec<Arc<Map>>,
+    view: View,
+}
+
+enum View {
+    Menu(MenuView),
+    Game(SnakeGame),
+    /// Game playing itself until a key is pressed.
+    Demo(SnakeGame),
+    Editor(Box<MapEditor>),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MenuPage {
+    Main,
+    Settings,
+    HighScores,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MenuItem {
+    Play,
+    Level,
+    Mode,
+    Opponent,
+    Difficulty,
+    SpeedCurve,
+    Settings,
+    HighScores,
+    Quit,
+    Colors,
+    Back,
+}
+
+/// Title screen with the menu of a page under it.
+struct MenuView {
+    page: MenuPage,
+    title: TitleComponent,
+    leaderboard: Option<LeaderboardComponent>,
+    menu: MenuComponent,
+}
+
+impl MenuPage {
+    fn items(&self) -> &'static [MenuItem] {
+        match self {
+            MenuPage::Main => &[
+                MenuItem::Play,
+                MenuItem::Level,
+                MenuItem::Mode,
+                MenuItem::Opponent,
+                MenuItem::Difficulty,
+                MenuItem::SpeedCurve,
+                MenuItem::Settings,
+                MenuItem::HighScores,
+                MenuItem::Quit,
+            ],
+            MenuPage::Settings => &[MenuItem::Colors, MenuItem::Back],
+            MenuPage::HighScores => &[MenuItem::Back],
+        }
+    }
+}
+
+impl MenuItem {
+    fn label(&self, settings: &Settings) -> String {
+        match self {
+            MenuItem::Play => "Play".to_string(),
+            MenuItem::Level => format!(
+                "Level: {}",
+                settings.map.as_ref().map_or("None", |map| map.name())
+            ),
+            MenuItem::Mode => format!("Mode: {}", settings.mode.name()),
+            MenuItem::Opponent => format!("Opponent: {}", settings.opponent.name()),
+            MenuItem::Difficulty => format!("Difficulty: {}", settings.difficulty.name()),
+            MenuItem::SpeedCurve => format!("Speed: {}", settings.speed_curve.name()),
+            MenuItem::Settings => "Settings".to_string(),
+            MenuItem::HighScores => "High Scores".to_string(),
+            MenuItem::Quit => "Quit".to_string(),
+            MenuItem::Colors => format!("Colors: {}", settings.color_depth_name()),
+            MenuItem::Back => "Back".to_string(),
+        }
+    }
+}
+
+impl Application {
+    /// Show the title screen, or go right away to the replay or to the map editor.
+    ///
+    /// The map is the one edited with the editor, or the level selected at the start.
+    pub async fn new(
+        screen: SharedScreen<Stdout>,
+        cli: Cli,
+        replay: Option<Replay>,
+        map: Option<Map>,
+    ) -> component::Result<Self> {
+        let (edited_map, map) = match cli.command {
+            Some(Command::Edit { .. }) => (map, None),
+            Some(Command::Tournament(_) | Command::Agent(_)) | None => (None, map.map(Arc::new)),
+        };
+        let mut maps: Vec<Arc<Map>> = Map::built_in().into_iter().map(Arc::new).collect();
+        if let Some(map) = map.as_ref().filter(|map| !maps.contains(map)) {
+            maps.push(Arc::clone(map));
+        }
+        let settings = Settings {
+            mode: cli.mode,
+            opponent: cli.opponent,
+            computer_skill: Skill {
+                reaction_delay: cli.computer_delay,
+                mistake_rate: cli.computer_mistakes,
+            },
+            map,
+            difficulty: cli.difficulty,
+            speed_curve: cli.speed_curve,
+            fruit_rates: FruitRates {
+                golden: cli.golden_rate,
+                shrink: cli.shrink_rate,
+                slow_motion: cli.slow_motion_rate,
+                poison: cli.poison_rate,
+            },
+            ..Settings::default()
+        };
+        let view = View::Menu(MenuView::new(
+            SharedScreen::clone(&screen),
+            MenuPage::Main,
+            &settings,
+            None,
+        )?);
+        let mut application = Self {
+            screen,
+            settings,
+            seed: cli.seed,
+            record_path: cli.record,
+            high_scores_path: HighScores::default_path(),
+            maps,
+            view,
+        };
+        match (cli.command, replay) {
+            (Some(Command::Edit { path }), _) => application.start_editor(path, edited_map)?,
+            (None, Some(replay)) => application.start_game(Some(replay)).await?,
+            (None, None) if cli.demo => application.start_demo().await?,
+            (None, None) => application.show_menu(MenuPage::Main, 0)?,
+            (Some(Command::Tournament(_) | Command::Agent(_)), _) => {
+                unreachable!("The tournament and the agent are played without the terminal.")
+            }
+        }
+        Ok(application)
+    }
+
+    /// Apply the input to the current screen, return false when the program should end.
+    pub async fn handle_input(&mut self, input: GameInput) -> component::Result<bool> {
+        match (&mut self.view, input) {
+            (_, GameInput::Quit) => return Ok(false),
+            (View::Game(game), GameInput::Resize(width, height)) => {
+                game.resize(Dimension::new(width, height)).await?
+            }
+            (View::Menu(view), GameInput::Resize(width, height)) => {
+                let (page, selected) = (view.page, view.menu.selected());
+                self.screen.lock()?.resize(width, height)?;
+                self.show_menu(page, selected)?;
+            }
+            (View::Demo(demo), GameInput::Resize(width, height)) => {
+                // The cycle followed by the demo only fit the board it was made for.
+                demo.stop().await;
+                self.screen.lock()?.resize(width, height)?;
+                self.start_demo().await?;
+            }
+            (View::Demo(demo), _) => {
+                demo.stop().await;
+                self.start_game(None).await?;
+            }
+            (View::Editor(editor), GameInput::Resize(width, height)) => {
+                editor.resize(Dimension::new(width, height))?
+            }
+            (View::Editor(editor), GameInput::Back) => {
+                // The map in the file can be played right away.
+                if let Some(map) = editor.saved_map().clone() {
+                    let map = Arc::new(map);
+                    self.maps.push(Arc::clone(&map));
+                    self.settings.map = Some(map);
+                }
+                self.show_menu(MenuPage::Main, 0)?;
+            }
+            (View::Editor(editor), input) => match input {
+                GameInput::Up => editor.move_cursor(Direction::Up)?,
+                GameInput::Down => editor.move_cursor(Direction::Down)?,
+                GameInput::Left => editor.move_cursor(Direction::Left)?,
+                GameInput::Right => editor.move_cursor(Direction::Right)?,
+                GameInput::Pause | GameInput::Char('w') => editor.toggle_wall()?,
+                GameInput::Char('a') => editor.toggle_apple_spawn()?,
+                GameInput::Char('s') => editor.place_snake()?,
+                GameInput::Select => editor.save()?,
+                _ => {}
+            },
+            (View::Game(game), GameInput::Back) => {
+                game.stop().await;
+                self.show_menu(MenuPage::Main, 0)?;
+            }
+            (View::Game(game), input) => match input {
+                GameInput::Up => game.change_direction(Player::One, Direction::Up).await,
+                GameInput::Down => game.change_direction(Player::One, Direction::Down).await,
+                GameInput::Left => game.change_direction(Player::One, Direction::Left).await,
+                GameInput::Right => game.change_direction(Player::One, Direction::Right).await,
+                // The second player use WASD, ignored without a second player.
+                GameInput::Char('w') => game.change_direction(Player::Two, Direction::Up).await,
+                GameInput::Char('s') => game.change_direction(Player::Two, Direction::Down).await,
+                GameInput::Char('a') => game.change_direction(Player::Two, Direction::Left).await,
+                GameInput::Char('d') => game.change_direction(Player::Two, Direction::Right).await,
+                GameInput::Pause => game.toggle_pause().await?,
+                GameInput::Restart => game.restart().await?,
+                _ => {}
+            },
+            (View::Menu(_), input) => return self.handle_menu_input(input).await,
+        }
+        Ok(true)
+    }
+
+    async fn handle_menu_input(&mut self, input: GameInput) -> component::Result<bool> {
+        let View::Menu(view) = &mut self.view else {
+            return Ok(true);
+        };
+        let page = view.page;
+        let item = page.items()[view.menu.selected()];
+        match input {
+            GameInput::Up => view.menu.select_previous(),
+            GameInput::Down => view.menu.select_next(),
+            GameInput::Left => return self.change_setting(item, false).map(|_| true),
+            GameInput::Right => return self.change_setting(item, true).map(|_| true),
+            GameInput::Select => match item {
+                MenuItem::Play => {
+                    self.start_game(None).await?;
+                    return Ok(true);
+                }
+                MenuItem::Level
+                | MenuItem::Mode
+                | MenuItem::Opponent
+                | MenuItem::Difficulty
+                | MenuItem::SpeedCurve
+                | MenuItem::Colors => return self.change_setting(item, true).map(|_| true),
+                MenuItem::Settings => return self.show_menu(MenuPage::Settings, 0).map(|_| true),
+                MenuItem::HighScores => {
+                    return self.show_menu(MenuPage::HighScores, 0).map(|_| true)
+                }
+                MenuItem::Quit => return Ok(false),
+                MenuItem::Back => return self.go_back(page).map(|_| true),
+            },
+            GameInput::Back if page == MenuPage::Main => return Ok(false),
+            GameInput::Back => return self.go_back(page).map(|_| true),
+            _ => return Ok(true),
+        }
+        self.render_menu()?;
+        Ok(true)
+    }
+
+    /// Change the setting of the menu item to its next or previous value.
+    fn change_setting(&mut self, item: MenuItem, forward: bool) -> component::Result<()> {
+        let View::Menu(view) = &self.view else {
+            return Ok(());
+        };
+        let (page, selected) = (view.page, view.menu.selected());
+        match (item, forward) {
+            (MenuItem::Level, forward) => self.cycle_map(forward),
+            (MenuItem::Mode, true) => self.settings.mode = self.settings.mode.next(),
+            (MenuItem::Mode, false) => self.settings.mode = self.settings.mode.previous(),
+            (MenuItem::Opponent, true) => self.settings.opponent = self.settings.opponent.next(),
+            (MenuItem::Opponent, false) => {
+                self.settings.opponent = self.settings.opponent.previous()
+            }
+            (MenuItem::Difficulty, true) => {
+                self.settings.difficulty = self.settings.difficulty.next()
+            }
+            (MenuItem::Difficulty, false) => {
+                self.settings.difficulty = self.settings.difficulty.previous()
+            }
+            (MenuItem::SpeedCurve, true) => {
+                self.settings.speed_curve = self.settings.speed_curve.next()
+            }
+            (MenuItem::SpeedCurve, false) => {
+                self.settings.speed_curve = self.settings.speed_curve.previous()
+            }
+            (MenuItem::Colors, forward) => {
+                self.settings.cycle_color_depth(forward);
+                // What is already displayed must be written again with the new colors.
+                self.screen.lock()?.clear_screen()?;
+            }
+            _ => return Ok(()),
+        }
+        // The label change, so the menu is centered again.
+        self.show_menu(page, selected)
+    }
+
+    /// Go to the next or the previous level, no level come after the last one.
+    fn cycle_map(&mut self, forward: bool) {
+        let count = self.maps.len() + 1;
+        // The index after the last level is no level.
+        let index = self
+            .settings
+            .map
+            .as_ref()
+            .and_then(|map| self.maps.iter().position(|m| m == map))
+            .unwrap_or(self.maps.len());
+        let index = if forward {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        };
+        self.settings.map = self.maps.get(index).cloned();
+    }
+
+    /// Show the main menu with the item that lead to the page selected.
+    fn go_back(&mut self, page: MenuPage) -> component::Result<()> {
+        let item = match page {
+            MenuPage::Main => MenuItem::Play,
+            MenuPage::Settings => MenuItem::Settings,
+            MenuPage::HighScores => MenuItem::HighScores,
+        };
+        let selected = MenuPage::Main
+            .items()
+            .iter()
+            .position(|i| *i == item)
+            .unwrap_or_default();
+        self.show_menu(MenuPage::Main, selected)
+    }
+
+    fn show_menu(&mut self, page: MenuPage, selected: usize) -> component::Result<()> {
+        let high_scores = match page {
+            MenuPage::HighScores => Some(
+                self.high_scores_path
+                    .as_ref()
+                    .and_then(|path| HighScores::load(path).ok())
+                    .unwrap_or_default(),
+            ),
+            _ => None,
+        };
+        let mut view = MenuView::new(
+            SharedScreen::clone(&self.screen),
+            page,
+            &self.settings,
+            high_scores.as_ref(),
+        )?;
+        view.menu.select(selected);
+        // The game is dropped before the menu is drawn, so what it erase is drawn over.
+        self.view = View::Menu(view);
+        self.render_menu()
+    }
+
+    fn render_menu(&self) -> component::Result<()> {
+        let View::Menu(view) = &self.view else {
+            return Ok(());
+        };
+        self.screen.lock()?.buffer_mut().clear();
+        view.title.render()?;
+        if let Some(leaderboard) = &view.leaderboard {
+            leaderboard.render()?;
+        }
+        view.menu.render()?;
+        self.screen.lock()?.present()?;
+        Ok(())
+    }
+
+    fn start_editor(&mut self, path: PathBuf, map: Option<Map>) -> component::Result<()> {
+        self.screen.lock()?.clear_screen()?;
+        let editor = MapEditor::new(SharedScreen::clone(&self.screen), path, map)?;
+        editor.render()?;
+        self.view = View::Editor(Box::new(editor));
+        Ok(())
+    }
+
+    /// Start the demo when nothing happened for a while on the main menu.
+    pub async fn idle(&mut self) -> component::Result<()> {
+        match &self.view {
+            View::Menu(view) if view.page == MenuPage::Main => self.start_demo().await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Let the game play itself on an empty board, it never dies and fills the board.
+    async fn start_demo(&mut self) -> component::Result<()> {
+        self.screen.lock()?.clear_screen()?;
+        let screen = &self.screen;
+        let state = layout::create_application_demo_game_state(
+            SharedScreen::clone(screen),
+            rand::random(),
+        )?;
+        let game_board_boundary = state.lock().await.boundary();
+        let mut demo = SnakeGame::new(
+            SharedScreen::clone(screen),
+            state,
+            layout::create_application_timer(SharedScreen::clone(screen))?,
+            layout::create_application_game_board(SharedScreen::clone(screen))?,
+            layout::create_application_game_over_message(SharedScreen::clone(screen))?,
+            layout::create_application_pause(SharedScreen::clone(screen))?,
+            layout::create_application_border(
+                SharedScreen::clone(screen),
+                game_board_boundary,
+                GameMode::Classic,
+            )?,
+        );
+        demo.set_difficulty(self.settings.difficulty);
+        demo.set_demo(Some(layout::create_application_demo(SharedScreen::clone(
+            screen,
+        ))?));
+        demo.render().await?;
+        demo.start_game_loop().await;
+        self.view = View::Demo(demo);
+        Ok(())
+    }
+
+    /// Start a round, the replay is played instead of the inputs when there is one.
+    async fn start_game(&mut self, replay: Option<Replay>) -> component::Result<()> {
+        self.screen.lock()?.clear_screen()?;
+        let screen = &self.screen;
+        let seed = self.seed.take().unwrap_or_else(rand::random);
+        // A replay is played with the rules it was recorded with.
+        let mode = replay.as_ref().map_or(self.settings.mode, Replay::mode);
+        let opponent = replay
+            .as_ref()
+            .map_or(self.settings.opponent, Replay::opponent);
+        let state = layout::create_application_game_state(
+            SharedScreen::clone(screen),
+            seed,
+            mode,
+            opponent,
+            self.settings.map.clone(),
+            self.settings.fruit_rates,
+        )?;
+        let game_board_boundary = state.lock().await.boundary();
+        let mut game = SnakeGame::new(
+            SharedScreen::clone(screen),
+            state,
+            layout::create_application_timer(SharedScreen::clone(screen))?,
+            layout::create_application_game_board(SharedScreen::clone(screen))?,
+            layout::create_application_game_over_message(SharedScreen::clone(screen))?,
+            layout::create_application_pause(SharedScreen::clone(screen))?,
+            layout::create_application_border(
+                SharedScreen::clone(screen),
+                game_board_boundary,
+                mode,
+            )?,
+        );
+        game.set_record_path(self.record_path.clone());
+        game.set_high_scores_path(self.high_scores_path.clone());
+        game.set_difficulty(self.settings.difficulty);
+        game.set_speed_curve(self.settings.speed_curve);
+        game.set_computer_skill(self.settings.computer_skill);
+        if let Some(replay) = replay {
+            game.set_playback(replay).await?;
+        }
+        game.render().await?;
+        game.start_game_loop().await;
+        self.view = View::Game(game);
+        Ok(())
+    }
+}
+
+impl MenuView {
+    fn new(
+        screen: SharedScreen<Stdout>,
+        page: MenuPage,
+        settings: &Settings,
+        high_scores: Option<&HighScores>,
+    ) -> component::Result<Self> {
+        let screen_dimension: Dimension = screen.lock()?.size().into();
+        let items: Vec<String> = page
+            .items()
+            .iter()
+            .map(|item| item.label(settings))
+            .collect();
+        let menu_dimension = menu_dimension(&items);
+        // Only the high scores of the settings chosen are shown, with the settings above them.
+        let leaderboard = high_scores.map(|high_scores| {
+            let round_settings = RoundSettings::new(
+                settings.mode,
+                settings.difficulty,
+                settings.speed_curve,
+                settings.map.as_deref(),
+                settings.fruit_rates,
+            );
+            let mut leaderboard = LeaderboardComponent::new(
+                SharedScreen::clone(&screen),
+                Position::new(0, 0),
+                LEADERBOARD_WIDTH,
+            );
+            leaderboard.set_rows(
+                high_scores
+                    .with_settings(&round_settings)
+                    .cloned()
+                    .enumerate()
+                    .collect(),
+                None,
+            );
+            leaderboard.set_caption(Some(round_settings.description()));
+            leaderboard
+        });
+        // The leaderboard and an empty line are above the menu.
+        let leaderboard_height = leaderboard
+            .as_ref()
+            .map_or(0, |leaderboard| leaderboard.height() + 1);
+        let content_dimension = Dimension::new(
+            if leaderboard.is_some() {
+                LEADERBOARD_WIDTH
+            } else {
+                menu_dimension.width
+            },
+            leaderboard_height + menu_dimension.height,
+        );
+        let (title_position, content_position) =
+            layout::get_title_and_content_positions(screen_dimension, content_dimension);
+        let leaderboard = leaderboard.map(|mut leaderboard| {
+            leaderboard.set_position(content_position);
+            leaderboard
+        });
+        let mut menu_position = content_position;
+        menu_position.y += leaderboard_height;
+        menu_position.x = (screen_dimension.width.saturating_sub(menu_dimension.width) / 2).max(1);
+
+        Ok(Self {
+            page,
+            title: TitleComponent::new(SharedScreen::clone(&screen), title_position),
+            leaderboard,
+            menu: MenuComponent::new(screen, menu_position, items),
+        })
+    }
+}
@@ -0,0 +1,128 @@
+use clap::builder::PossibleValue;
+
+use crate::{
+    computer::{hamiltonian::CycleFollower, ComputerPlayer, Skill},
+    controller::Controller,
+    game_state::GameState,
+};
+
+/// Build the controller of a bot at the start of a round.
+pub type ControllerFactory = Box<dyn Fn(&GameState) -> Box<dyn Controller> + Send + Sync>;
+
+/// Bot moving the snake of the computer opponent.
+pub const COMPUTER: &str = "computer";
+/// Bot moving the snake of the demo.
+pub const CYCLE: &str = "cycle";
+
+/// Bot known by its name, in the tournament and in the game.
+pub struct Bot {
+    name: &'static str,
+    /// Help of the bot on the command line.
+    description: &'static str,
+    factory: ControllerFactory,
+    can_play: fn(&GameState) -> bool,
+}
+
+impl Bot {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Controller moving the snake of the bot during the round.
+    pub fn controller(&self, state: &GameState) -> Box<dyn Controller> {
+        (self.factory)(state)
+    }
+
+    /// True when the bot can play the round, some bots need a board of a certain kind.
+    pub fn can_play(&self, state: &GameState) -> bool {
+        (self.can_play)(state)
+    }
+}
+
+/// Every bot which can move a snake.
+///
+/// A new bot implements the `Controller` trait and is registered in `BotRegistry::new`,
+/// then it can enter the tournament with `--bot <name>`.
+pub struct BotRegistry {
+    bots: Vec<Bot>,
+}
+
+impl BotRegistry {
+    /// The bots of the game, the computer ones play with the skill.
+    pub fn new(skill: Skill) -> Self {
+        let mut registry = Self { bots: Vec::new() };
+        registry.register(
+            COMPUTER,
+            "The computer opponent, with the skill given by `--computer-delay` \
+             and `--computer-mistakes`.",
+            move |state| Box::new(ComputerPlayer::new(skill, state.seed())),
+        );
+        registry.register(
+            "greedy",
+            "The computer opponent without reaction delay nor mistakes.",
+            |state| {
+                let skill = Skill {
+                    reaction_delay: 0,
+                    mistake_rate: 0,
+                };
+                Box::new(ComputerPlayer::new(skill, state.seed()))
+            },
+        );
+        registry.register_with_condition(
+            CYCLE,
+            "The snake of the demo, following a cycle over the whole board. \
+             It needs a board with an even side and without level.",
+            |state| Box::new(CycleFollower::new(state.boundary())),
+            |state| {
+                let board = state.boundary();
+                state.map().is_none()
+                    && CycleFollower::board_for(board).dimension() == board.dimension()
+            },
+        );
+        registry
+    }
+
+    /// Add a bot which can play every round.
+    pub fn register(
+        &mut self,
+        name: &'static str,
+        description: &'static str,
+        factory: impl Fn(&GameState) -> Box<dyn Controller> + Send + Sync + 'static,
+    ) {
+        self.register_with_condition(name, description, factory, |_| true);
+    }
+
+    /// Add a bot which only play the rounds meeting the condition.
+    pub fn register_with_condition(
+        &mut self,
+        name: &'static str,
+        description: &'static str,
+        factory: impl Fn(&GameState) -> Box<dyn Controller> + Send + Sync + 'static,
+        can_play: fn(&GameState) -> bool,
+    ) {
+        self.bots.push(Bot {
+            name,
+            description,
+            factory: Box::new(factory),
+            can_play,
+        });
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Bot> {
+        self.bots.iter().find(|bot| bot.name == name)
+    }
+
+    /// Bots in the order they were registered.
+    pub fn bots(&self) -> &[Bot] {
+        &self.bots
+    }
+
+    /// Names of the bots, for the `--bot` option.
+    pub fn possible_values() -> Vec<PossibleValue> {
+        Self::new(Skill::default())
+            .bots
+            .iter()
+            .map(|bot| PossibleValue::new(bot.name).help(bot.description))
+            .collect()
+    }
+}
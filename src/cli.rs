@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+
+use clap::{builder::PossibleValuesParser, Args, Parser, Subcommand};
+
+use crate::{
+    bot::BotRegistry,
+    computer::Skill,
+    game_state::fruit::FruitRates,
+    settings::{Difficulty, GameMode, Opponent, SpeedCurve},
+    tournament::ResultFormat,
+};
+
+/// Longest side of the board of the agent, so the screen around it is still in the coordinates.
+pub const MAX_AGENT_BOARD_SIDE: u16 = u16::MAX - 5;
+
+/// Snake game in the terminal.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Seed of the random number generator, a random one is used when not given.
+    ///
+    /// The same seed gives the same sequence of apples.
+    #[arg(long, conflicts_with = "replay")]
+    pub seed: Option<u64>,
+
+    /// Save the replay of each round to the file once the game is over.
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Built-in level (box, cross, pillars or rooms) or map file to play.
+    #[arg(long, value_name = "LEVEL", conflicts_with = "replay")]
+    pub level: Option<String>,
+
+    /// Rules of the rounds.
+    #[arg(long, value_enum, default_value_t)]
+    pub mode: GameMode,
+
+    /// Who controls the second snake, player 2 use WASD.
+    #[arg(long, value_enum, default_value_t)]
+    pub opponent: Opponent,
+
+    /// Steps before the computer notices where a new apple is.
+    #[arg(long, value_name = "TICKS", default_value_t = Skill::default().reaction_delay)]
+    pub computer_delay: u64,
+
+    /// Chance, in percent, that the computer turns at random instead of following its path.
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = Skill::default().mistake_rate)]
+    pub computer_mistakes: u8,
+
+    /// Speed of the snake at the start of a round.
+    #[arg(long, value_enum, default_value_t)]
+    pub difficulty: Difficulty,
+
+    /// Speed up the snake as it grow or as the score increase.
+    #[arg(long, value_enum, default_value_t)]
+    pub speed_curve: SpeedCurve,
+
+    /// Chance, in percent, that a golden fruit spawn when an apple is eaten.
+    ///
+    /// It is worth more than an apple but vanishes quickly.
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = FruitRates::default().golden)]
+    pub golden_rate: u8,
+
+    /// Chance, in percent, that a fruit removing the end of the tail spawn when an apple is eaten.
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = FruitRates::default().shrink)]
+    pub shrink_rate: u8,
+
+    /// Chance, in percent, that a fruit slowing down the game spawn when an apple is eaten.
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = FruitRates::default().slow_motion)]
+    pub slow_motion_rate: u8,
+
+    /// Chance, in percent, that a poison costing points spawn when an apple is eaten.
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100),
+        default_value_t = FruitRates::default().poison)]
+    pub poison_rate: u8,
+
+    /// Let the game play itself until a key is pressed.
+    #[arg(long, conflicts_with = "replay")]
+    pub demo: bool,
+
+    /// Play the replay saved in the file.
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Edit a map file in the terminal, it is created when it does not exist.
+    Edit {
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+    },
+    /// Play seeded rounds with each bot without drawing them, then rank the bots.
+    ///
+    /// The rounds use the mode, level and fruit rates given before the subcommand,
+    /// and the seeds from `--seed` onward.
+    Tournament(TournamentArgs),
+    /// Let another program play through JSON lines on stdin and stdout.
+    ///
+    /// Each line read is a request, `{"type": "reset"}`, `{"type": "step", "direction": "up"}`,
+    /// `{"type": "observe"}` or `{"type": "close"}`, and each line written is the observation of
+    /// the board with the reward of the step. The rounds use the mode, level and fruit rates
+    /// given before the subcommand.
+    Agent(AgentArgs),
+}
+
+#[derive(Args)]
+pub struct TournamentArgs {
+    /// Bots to rank, every bot which can play on the board when not given.
+    #[arg(long = "bot", value_name = "BOT",
+        value_parser = PossibleValuesParser::new(BotRegistry::possible_values()))]
+    pub bots: Vec<String>,
+
+    /// Rounds played by each bot.
+    #[arg(long, default_value_t = 100)]
+    pub rounds: u64,
+
+    /// Width of the board, a level is never bigger than its map.
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..), default_value_t = 40)]
+    pub width: u16,
+
+    /// Height of the board, a level is never bigger than its map.
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..), default_value_t = 20)]
+    pub height: u16,
+
+    /// Steps after which a round still going is stopped.
+    #[arg(long, value_name = "TICKS", default_value_t = 100_000)]
+    pub max_ticks: u64,
+
+    /// Save the result of every round to the file.
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Format of the saved results.
+    #[arg(long, value_enum, default_value_t)]
+    pub format: ResultFormat,
+}
+
+#[derive(Args)]
+pub struct AgentArgs {
+    /// Width of the board, a level is never bigger than its map.
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..=MAX_AGENT_BOARD_SIDE as i64),
+        default_value_t = 40)]
+    pub width: u16,
+
+    /// Height of the board, a level is never bigger than its map.
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..=MAX_AGENT_BOARD_SIDE as i64),
+        default_value_t = 20)]
+    pub height: u16,
+
+    /// Terminal where the game is drawn, like `/dev/pts/3`, nothing is drawn when not given.
+    #[arg(long, value_name = "TTY")]
+    pub tty: Option<PathBuf>,
+}
@@ -1,16 +1,31 @@
 pub mod apple;
 pub mod border;
+pub mod demo;
+pub mod fruit;
 pub mod game_board;
 pub mod game_over;
+pub mod leaderboard;
+pub mod level;
+pub mod map_board;
+pub mod menu;
+pub mod pause;
 pub mod score;
 pub mod snake;
+pub mod status_bar;
 pub mod timer;
+pub mod title;
+pub mod walls;
 
-use std::ops::Add;
+use std::{io::Write, ops::Add};
 
 use accessors_rs::Accessors;
 use error_chain::error_chain;
 use rand::Rng;
+use snake_in_terminal::terminus::{buffer::Cell, screen::Screen};
+#[cfg(test)]
+use snake_in_terminal::terminus::{
+    screen::SharedScreen, style::ColorDepth, virtual_terminal::VirtualTerminal,
+};
 
 error_chain! {
     errors {
@@ -19,6 +34,7 @@ error_chain! {
 
     links {
         Screen(snake_in_terminal::terminus::screen::Error, snake_in_terminal::terminus::screen::ErrorKind);
+        Replay(crate::replay::Error, crate::replay::ErrorKind);
     }
 
     foreign_links {
@@ -66,8 +82,7 @@ impl Dimension {
         Self { width, height }
     }
 
-    pub fn get_random_position_inside(&self) -> Position {
-        let mut rng = rand::thread_rng();
+    pub fn get_random_position_inside(&self, rng: &mut impl Rng) -> Position {
         Position::new(rng.gen_range(0..self.width), rng.gen_range(0..self.height))
     }
 }
@@ -116,3 +131,40 @@ impl Boundary {
         self.position.x + self.dimension.width - 1
     }
 }
+
+/// Cells hidden by an overlay, kept to restore them when the overlay is erased.
+pub struct HiddenCells {
+    cells: Vec<(Position, Cell)>,
+}
+
+impl HiddenCells {
+    pub fn capture<T: Write + Send>(screen: &Screen<T>, boundary: Boundary) -> Self {
+        let mut cells = Vec::new();
+        for y in boundary.top()..=boundary.bottom() {
+            for x in boundary.left()..=boundary.right() {
+                if let Some(cell) = screen.buffer().get(x, y) {
+                    cells.push((Position::new(x, y), cell.clone()));
+                }
+            }
+        }
+        Self { cells }
+    }
+
+    pub fn restore<T: Write + Send>(self, screen: &mut Screen<T>) {
+        for (Position { x, y }, cell) in self.cells {
+            screen.draw_char(x, y, cell.symbol(), cell.style());
+        }
+    }
+}
+
+/// Screen drawing in a virtual terminal of the same size, for the snapshot tests of the components.
+///
+/// The colors stay in true color, so the snapshots do not depend on the terminal running the tests.
+#[cfg(test)]
+pub fn virtual_screen(width: u16, height: u16) -> (SharedScreen<VirtualTerminal>, VirtualTerminal) {
+    let terminal = VirtualTerminal::new(width, height);
+    let output = terminal.clone();
+    let mut screen = Screen::with_size(move || output.clone(), width, height);
+    screen.set_color_depth(ColorDepth::TrueColor);
+    (SharedScreen::new(screen), terminal)
+}
@@ -1,6 +1,5 @@
-use std::io::Stdout;
+use std::io::{Stdout, Write};
 
-use accessors_rs::Accessors;
 use snake_in_terminal::terminus::{
     screen::SharedScreen,
     style::{Color, Style, StyleProperty},
@@ -8,50 +7,53 @@ use snake_in_terminal::terminus::{
 
 use super::Position;
 
-#[derive(Accessors)]
-pub struct AppleComponent {
+pub struct AppleComponent<T: Write + Send = Stdout> {
     style: Style,
-    #[accessors(get_copy)]
-    position: Position,
-    screen: SharedScreen<Stdout>,
+    screen: SharedScreen<T>,
 }
 
-impl AppleComponent {
-    pub fn new(screen: SharedScreen<Stdout>, position: Position) -> super::Result<Self> {
-        let apple = Self {
-            position,
+impl<T: Write + Send> AppleComponent<T> {
+    pub fn new(screen: SharedScreen<T>) -> Self {
+        Self {
             screen,
             style: Style::from([
                 StyleProperty::Bold,
                 StyleProperty::Color(Color::RGB(235, 35, 55)),
             ]),
-        };
-        apple.render()?;
-        Ok(apple)
+        }
     }
 
-    fn render(&self) -> super::Result<()> {
+    pub fn render(&self, position: Position) -> super::Result<()> {
         let mut screen = self.screen.lock()?;
-        let Position { x, y } = self.position;
-        screen.cursor_mut().move_to(x, y)?;
-        screen.write_str(self.style.ansi_sequence().as_str())?;
-        screen.write_str("▄")?;
-        screen.write_str(Style::RESET)?;
-        Ok(())
-    }
-
-    fn erase(&self) -> super::Result<()> {
-        if let Ok(mut screen) = self.screen.lock() {
-            let Position { x, y } = self.position;
-            screen.cursor_mut().move_to(x, y)?;
-            screen.write_str(" ")?;
-        }
+        let Position { x, y } = position;
+        screen.draw_char(x, y, '▄', &self.style);
         Ok(())
     }
 }
 
-impl Drop for AppleComponent {
-    fn drop(&mut self) {
-        let _ = self.erase();
+#[cfg(test)]
+mod tests {
+    use snake_in_terminal::terminus::style::{Color, Style, StyleProperty};
+
+    use super::AppleComponent;
+    use crate::component::{virtual_screen, Position};
+
+    #[test]
+    fn render_apple_in_red() {
+        let (screen, terminal) = virtual_screen(5, 3);
+        AppleComponent::new(screen.clone())
+            .render(Position::new(3, 2))
+            .unwrap();
+        screen.lock().unwrap().present().unwrap();
+
+        assert_eq!(terminal.contents(), "\n  ▄\n\n");
+        let cell = terminal.cell(3, 2).unwrap();
+        assert_eq!(
+            cell.style(),
+            &Style::from([
+                StyleProperty::Bold,
+                StyleProperty::Color(Color::RGB(235, 35, 55)),
+            ])
+        );
     }
 }
@@ -1,4 +1,4 @@
-use std::io::Stdout;
+use std::io::{Stdout, Write};
 
 use snake_in_terminal::terminus::{screen::SharedScreen, style::Style};
 
@@ -10,39 +10,61 @@ const BOTTOM_RIGHT_CORNER: char = '╯';
 const BOTTOM_LEFT_CORNER: char = '╰';
 const LINE: char = '─';
 const COLUMN: char = '│';
+const OPEN_LINE: char = '┄';
+const OPEN_COLUMN: char = '┆';
 
-pub struct BorderComponent {
-    screen: SharedScreen<Stdout>,
+pub struct BorderComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
     style: Style,
     boundary: Boundary,
+    /// Drawn with dashed lines, for the walls the snake go through.
+    is_open: bool,
 }
 
-impl BorderComponent {
-    pub fn new(screen: SharedScreen<Stdout>, boundary: Boundary, style: Style) -> BorderComponent {
+impl<T: Write + Send> BorderComponent<T> {
+    pub fn new(screen: SharedScreen<T>, boundary: Boundary, style: Style) -> BorderComponent<T> {
         Self {
             screen,
             style,
             boundary,
+            is_open: false,
         }
     }
 
     pub fn render(&self) -> super::Result<()> {
+        let (line, column) = if self.is_open {
+            (OPEN_LINE, OPEN_COLUMN)
+        } else {
+            (LINE, COLUMN)
+        };
         self.render_border_with(
             TOP_LEFT_CORNER,
             TOP_RIGHT_CORNER,
             BOTTOM_RIGHT_CORNER,
             BOTTOM_LEFT_CORNER,
-            LINE,
-            COLUMN,
-            LINE,
-            COLUMN,
+            line,
+            column,
+            line,
+            column,
         )
     }
 
+    /// Draw the border with dashed lines from the next render.
+    pub fn set_open(&mut self, is_open: bool) {
+        self.is_open = is_open;
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) -> super::Result<()> {
+        self.erase()?;
+        self.boundary = boundary;
+        self.render()
+    }
+
     pub fn erase(&self) -> super::Result<()> {
         self.render_border_with(' ', ' ', ' ', ' ', ' ', ' ', ' ', ' ')
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn render_border_with(
         &self,
         top_left_corner: char,
@@ -56,47 +78,85 @@ impl BorderComponent {
     ) -> super::Result<()> {
         let Position { x, y } = self.boundary.position();
         let Dimension { width, height } = self.boundary.dimension();
+        let right = x + width - 1;
+        let bottom = y + height - 1;
         let mut screen = self.screen.lock()?;
 
-        screen.write_str(&self.style.ansi_sequence())?;
-
-        // Top left corner.
-        screen.cursor_mut().move_to(x, y)?;
-        screen.write_char(top_left_corner)?;
         // Top line.
-        screen.write_str(&top_line.to_string().repeat((width - 2) as usize))?;
-        // Top right corner.
-        screen.write_char(top_right_corner)?;
-        // Right column.
-        for _ in 1..height - 1 {
-            if x + width - 1 < width {
-                screen.cursor_mut().move_left(1)?;
-            }
-            screen.cursor_mut().move_down(1)?;
-            screen.write_char(right_line)?;
+        screen.draw_char(x, y, top_left_corner, &self.style);
+        screen.draw_str(
+            x + 1,
+            y,
+            &top_line
+                .to_string()
+                .repeat(width.saturating_sub(2) as usize),
+            &self.style,
+        );
+        screen.draw_char(right, y, top_right_corner, &self.style);
+        // Left and right columns.
+        for y in y + 1..bottom {
+            screen.draw_char(x, y, left_line, &self.style);
+            screen.draw_char(right, y, right_line, &self.style);
         }
-        // Left column.
-        screen.cursor_mut().move_to(x, y)?;
-        for _ in 1..height - 1 {
-            screen.cursor_mut().move_down(1)?;
-            screen.write_char(left_line)?;
-            screen.cursor_mut().move_left(1)?;
-        }
-        // Bottom left corner.
-        screen.cursor_mut().move_down(1)?;
-        screen.write_char(bottom_left_corner)?;
-        // Bottom Line.
-        screen.write_str(&bottom_line.to_string().repeat((width - 2) as usize))?;
-        // Bottom right corner.
-        screen.write_char(bottom_right_corner)?;
-
-        screen.write_str(Style::RESET)?;
+        // Bottom line.
+        screen.draw_char(x, bottom, bottom_left_corner, &self.style);
+        screen.draw_str(
+            x + 1,
+            bottom,
+            &bottom_line
+                .to_string()
+                .repeat(width.saturating_sub(2) as usize),
+            &self.style,
+        );
+        screen.draw_char(right, bottom, bottom_right_corner, &self.style);
         Ok(())
     }
 }
 
-impl Drop for BorderComponent {
+impl<T: Write + Send> Drop for BorderComponent<T> {
     fn drop(&mut self) {
         let _ = self.erase();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use snake_in_terminal::terminus::style::Style;
+
+    use super::BorderComponent;
+    use crate::component::{virtual_screen, Boundary, Dimension, Position};
+
+    fn render(is_open: bool) -> String {
+        let (screen, terminal) = virtual_screen(8, 5);
+        let boundary = Boundary::new(Position::new(2, 1), Dimension::new(6, 4));
+        let mut border = BorderComponent::new(screen.clone(), boundary, Style::new());
+        border.set_open(is_open);
+        border.render().unwrap();
+        screen.lock().unwrap().present().unwrap();
+        terminal.contents()
+    }
+
+    #[test]
+    fn render_closed_border() {
+        let expected = [" ╭────╮", " │    │", " │    │", " ╰────╯", ""];
+        assert_eq!(render(false), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn render_open_border() {
+        let expected = [" ╭┄┄┄┄╮", " ┆    ┆", " ┆    ┆", " ╰┄┄┄┄╯", ""];
+        assert_eq!(render(true), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn erase_border_when_dropped() {
+        let (screen, terminal) = virtual_screen(4, 3);
+        let boundary = Boundary::new(Position::new(1, 1), Dimension::new(4, 3));
+        let border = BorderComponent::new(screen.clone(), boundary, Style::new());
+        border.render().unwrap();
+        drop(border);
+        screen.lock().unwrap().present().unwrap();
+
+        assert_eq!(terminal.contents(), "\n\n\n");
+    }
+}
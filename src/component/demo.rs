@@ -0,0 +1,34 @@
+use std::io::{Stdout, Write};
+
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Style, StyleProperty},
+};
+
+use super::Position;
+
+pub const DEMO_TEXT: &str = "DEMO - press any key to play";
+
+/// Line telling the game is playing itself, shown between the score and the timer.
+pub struct DemoComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    position: Position,
+    style: Style,
+}
+
+impl<T: Write + Send> DemoComponent<T> {
+    pub fn new(screen: SharedScreen<T>, position: Position) -> Self {
+        Self {
+            screen,
+            position,
+            style: Style::from([StyleProperty::Bold]),
+        }
+    }
+
+    pub fn render(&self) -> super::Result<()> {
+        let Position { x, y } = self.position;
+        let mut screen = self.screen.lock()?;
+        screen.draw_str(x, y, DEMO_TEXT, &self.style);
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use std::io::{Stdout, Write};
+
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Color, Style, StyleProperty},
+};
+
+use crate::game_state::fruit::FruitKind;
+
+use super::Position;
+
+/// Render a fruit, each kind of fruit has its own glyph and color.
+pub struct FruitComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+}
+
+impl<T: Write + Send> FruitComponent<T> {
+    pub fn new(screen: SharedScreen<T>) -> Self {
+        Self { screen }
+    }
+
+    pub fn render(&self, kind: FruitKind, position: Position) -> super::Result<()> {
+        let mut screen = self.screen.lock()?;
+        let Position { x, y } = position;
+        let (glyph, color) = match kind {
+            FruitKind::Golden => ('★', Color::RGB(255, 200, 40)),
+            FruitKind::Shrink => ('▼', Color::RGB(80, 200, 255)),
+            FruitKind::SlowMotion => ('◆', Color::RGB(150, 110, 255)),
+            FruitKind::Poison => ('✖', Color::RGB(90, 200, 60)),
+        };
+        let style = Style::from([StyleProperty::Bold, StyleProperty::Color(color)]);
+        screen.draw_char(x, y, glyph, &style);
+        Ok(())
+    }
+}
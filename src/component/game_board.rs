@@ -1,51 +1,107 @@
-use std::io::Stdout;
+use std::io::{Stdout, Write};
 
 use accessors_rs::Accessors;
 
-use snake_in_terminal::terminus::screen::SharedScreen;
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Color, Style},
+};
+
+use crate::{game_state::GameState, settings::Opponent};
 
 use super::{
-    apple::AppleComponent, score::ScoreComponent, snake::SnakeComponent, Boundary, Position,
+    apple::AppleComponent, fruit::FruitComponent, level::LevelComponent, score::ScoreComponent,
+    snake::SnakeComponent, walls::WallsComponent, Boundary, Position,
 };
 
+const SCORE_POSITION: Position = Position { x: 1, y: 1 };
+
+/// Render the walls, the apple, the fruit, the snakes, the scores and the level of a game state.
 #[derive(Accessors)]
 #[accessors(get, get_mut)]
-pub struct GameBoardComponent {
-    screen: SharedScreen<Stdout>,
+pub struct GameBoardComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    /// Boundary of the last state rendered.
     #[accessors(get_copy)]
     boundary: Boundary,
-    apple: AppleComponent,
-    snake_component: SnakeComponent,
-    score: ScoreComponent,
+    walls: WallsComponent<T>,
+    apple: AppleComponent<T>,
+    fruit: FruitComponent<T>,
+    snake_component: SnakeComponent<T>,
+    second_snake: SnakeComponent<T>,
+    computer_snake: SnakeComponent<T>,
+    score: ScoreComponent<T>,
+    second_score: ScoreComponent<T>,
+    level: LevelComponent<T>,
 }
 
-impl GameBoardComponent {
-    pub fn new(
-        screen: SharedScreen<Stdout>,
-        boundary: Boundary,
-    ) -> super::Result<GameBoardComponent> {
-        let apple = AppleComponent::new(
-            SharedScreen::clone(&screen),
-            boundary.position() + boundary.dimension().get_random_position_inside(),
-        )?;
-        Ok(Self {
-            screen: SharedScreen::clone(&screen),
-            apple,
-            snake_component: SnakeComponent::try_new(
+impl<T: Write + Send> GameBoardComponent<T> {
+    pub fn new(screen: SharedScreen<T>, boundary: Boundary) -> GameBoardComponent<T> {
+        Self {
+            walls: WallsComponent::new(SharedScreen::clone(&screen)),
+            apple: AppleComponent::new(SharedScreen::clone(&screen)),
+            fruit: FruitComponent::new(SharedScreen::clone(&screen)),
+            snake_component: SnakeComponent::new(SharedScreen::clone(&screen)),
+            second_snake: SnakeComponent::with_colors(
                 SharedScreen::clone(&screen),
-                boundary.position(),
-            )?,
-            score: ScoreComponent::try_new(SharedScreen::clone(&screen), Position::new(1, 1))?,
+                Color::RGB(45, 85, 170),
+                Color::RGB(95, 165, 235),
+            ),
+            computer_snake: SnakeComponent::with_colors(
+                SharedScreen::clone(&screen),
+                Color::RGB(190, 90, 20),
+                Color::RGB(240, 160, 60),
+            ),
+            score: ScoreComponent::new(SharedScreen::clone(&screen), SCORE_POSITION),
+            second_score: ScoreComponent::new(SharedScreen::clone(&screen), SCORE_POSITION),
+            level: LevelComponent::new(SharedScreen::clone(&screen), SCORE_POSITION),
+            screen,
             boundary,
-        })
+        }
+    }
+
+    /// Draw the whole board again from the state, the level is shown after the scores.
+    pub fn render(&mut self, state: &GameState, level: u32) -> super::Result<()> {
+        self.erase()?;
+        self.boundary = state.boundary();
+        self.walls.render(state.walls(), self.boundary)?;
+        if let Some(apple) = state.apple() {
+            self.apple.render(apple)?;
+        }
+        if let Some(fruit) = state.fruit() {
+            self.fruit.render(fruit.kind(), fruit.position())?;
+        }
+        self.snake_component.render(state.snake())?;
+        let mut position = SCORE_POSITION;
+        match state.second_snake() {
+            Some(second_snake) => {
+                match state.opponent() {
+                    Opponent::Computer => self.computer_snake.render(second_snake)?,
+                    _ => self.second_snake.render(second_snake)?,
+                }
+                self.score.render(position, "Player 1", state.score())?;
+                position.x += self.score.width() + 3;
+                self.second_score.render(
+                    position,
+                    state.opponent().name(),
+                    state.second_score(),
+                )?;
+                position.x += self.second_score.width() + 3;
+            }
+            None => {
+                self.score.render(position, "Score", state.score())?;
+                position.x += self.score.width() + 3;
+            }
+        }
+        self.level.render(position, level)
     }
 
-    pub fn generate_new_apple(&mut self) -> super::Result<()> {
-        let apple = AppleComponent::new(
-            SharedScreen::clone(&self.screen),
-            self.boundary.position() + self.boundary.dimension().get_random_position_inside(),
-        )?;
-        self.apple = apple;
+    fn erase(&self) -> super::Result<()> {
+        let mut screen = self.screen.lock()?;
+        let blank = " ".repeat(self.boundary.dimension().width as usize);
+        for y in self.boundary.top()..=self.boundary.bottom() {
+            screen.draw_str(self.boundary.left(), y, &blank, &Style::new());
+        }
         Ok(())
     }
 }
@@ -1,13 +1,23 @@
-use std::io::Stdout;
+use std::io::{Stdout, Write};
 
 use accessors_rs::Accessors;
-use snake_in_terminal::terminus::screen::SharedScreen;
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Style, StyleProperty},
+};
 
-use super::Position;
+use crate::{
+    game_state::{GameState, Player},
+    high_score::{HighScore, HighScores},
+    settings::Opponent,
+};
+
+use super::{leaderboard::LeaderboardComponent, Boundary, Dimension, HiddenCells, Position};
 
 pub const GAME_OVER_WIDTH: u16 = 74;
-pub const GAME_OVER_HEIGHT: u16 = 8;
-const GAME_OVER: [&'static str; GAME_OVER_HEIGHT as usize] = [
+pub const GAME_OVER_HEIGHT: u16 = BANNER_HEIGHT + 1 + LEADERBOARD_HEIGHT;
+const BANNER_HEIGHT: u16 = 8;
+const GAME_OVER: [&str; BANNER_HEIGHT as usize] = [
     " ██████╗  █████╗ ███╗   ███╗███████╗     ██████╗ ██╗   ██╗███████╗██████╗ ",
     "██╔════╝ ██╔══██╗████╗ ████║██╔════╝    ██╔═══██╗██║   ██║██╔════╝██╔══██╗",
     "██║  ███╗███████║██╔████╔██║█████╗      ██║   ██║██║   ██║█████╗  ██████╔╝",
@@ -15,44 +25,286 @@ const GAME_OVER: [&'static str; GAME_OVER_HEIGHT as usize] = [
     "╚██████╔╝██║  ██║██║ ╚═╝ ██║███████╗    ╚██████╔╝ ╚████╔╝ ███████╗██║  ██║",
     " ╚═════╝ ╚═╝  ╚═╝╚═╝     ╚═╝╚══════╝     ╚═════╝   ╚═══╝  ╚══════╝╚═╝  ╚═╝",
     "                                                                          ",
-    "                          Press q to quit game!                           ",
+    "              Press r to restart or q to go back to the menu!             ",
+];
+const VICTORY: [&str; BANNER_HEIGHT as usize] = [
+    "         ██╗   ██╗██╗ ██████╗████████╗ ██████╗ ██████╗ ██╗   ██╗          ",
+    "         ██║   ██║██║██╔════╝╚══██╔══╝██╔═══██╗██╔══██╗╚██╗ ██╔╝          ",
+    "         ██║   ██║██║██║        ██║   ██║   ██║██████╔╝ ╚████╔╝           ",
+    "         ╚██╗ ██╔╝██║██║        ██║   ██║   ██║██╔══██╗  ╚██╔╝            ",
+    "          ╚████╔╝ ██║╚██████╗   ██║   ╚██████╔╝██║  ██║   ██║             ",
+    "           ╚═══╝  ╚═╝ ╚═════╝   ╚═╝    ╚═════╝ ╚═╝  ╚═╝   ╚═╝             ",
+    "                                                                          ",
+    "              Press r to restart or q to go back to the menu!             ",
+];
+const PLAYER_ONE_WINS: [&str; BANNER_HEIGHT as usize] = [
+    "              ██████╗  ██╗   ██╗    ██╗██╗███╗   ██╗███████╗              ",
+    "              ██╔══██╗███║   ██║    ██║██║████╗  ██║██╔════╝              ",
+    "              ██████╔╝╚██║   ██║ █╗ ██║██║██╔██╗ ██║███████╗              ",
+    "              ██╔═══╝  ██║   ██║███╗██║██║██║╚██╗██║╚════██║              ",
+    "              ██║      ██║   ╚███╔███╔╝██║██║ ╚████║███████║              ",
+    "              ╚═╝      ╚═╝    ╚══╝╚══╝ ╚═╝╚═╝  ╚═══╝╚══════╝              ",
+    "                                                                          ",
+    "              Press r to restart or q to go back to the menu!             ",
+];
+const PLAYER_TWO_WINS: [&str; BANNER_HEIGHT as usize] = [
+    "            ██████╗ ██████╗    ██╗    ██╗██╗███╗   ██╗███████╗            ",
+    "            ██╔══██╗╚════██╗   ██║    ██║██║████╗  ██║██╔════╝            ",
+    "            ██████╔╝ █████╔╝   ██║ █╗ ██║██║██╔██╗ ██║███████╗            ",
+    "            ██╔═══╝ ██╔═══╝    ██║███╗██║██║██║╚██╗██║╚════██║            ",
+    "            ██║     ███████╗   ╚███╔███╔╝██║██║ ╚████║███████║            ",
+    "            ╚═╝     ╚══════╝    ╚══╝╚══╝ ╚═╝╚═╝  ╚═══╝╚══════╝            ",
+    "                                                                          ",
+    "              Press r to restart or q to go back to the menu!             ",
 ];
+const DRAW: [&str; BANNER_HEIGHT as usize] = [
+    "                    ██████╗ ██████╗  █████╗ ██╗    ██╗                    ",
+    "                    ██╔══██╗██╔══██╗██╔══██╗██║    ██║                    ",
+    "                    ██║  ██║██████╔╝███████║██║ █╗ ██║                    ",
+    "                    ██║  ██║██╔══██╗██╔══██║██║███╗██║                    ",
+    "                    ██████╔╝██║  ██║██║  ██║╚███╔███╔╝                    ",
+    "                    ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝ ╚══╝╚══╝                     ",
+    "                                                                          ",
+    "              Press r to restart or q to go back to the menu!             ",
+];
+const COMPUTER_WINS: [&str; BANNER_HEIGHT as usize] = [
+    "        ██████╗██████╗ ██╗   ██╗   ██╗    ██╗██╗███╗   ██╗███████╗        ",
+    "       ██╔════╝██╔══██╗██║   ██║   ██║    ██║██║████╗  ██║██╔════╝        ",
+    "       ██║     ██████╔╝██║   ██║   ██║ █╗ ██║██║██╔██╗ ██║███████╗        ",
+    "       ██║     ██╔═══╝ ██║   ██║   ██║███╗██║██║██║╚██╗██║╚════██║        ",
+    "       ╚██████╗██║     ╚██████╔╝   ╚███╔███╔╝██║██║ ╚████║███████║        ",
+    "        ╚═════╝╚═╝      ╚═════╝     ╚══╝╚══╝ ╚═╝╚═╝  ╚═══╝╚══════╝        ",
+    "                                                                          ",
+    "              Press r to restart or q to go back to the menu!             ",
+];
+/// Empty line of the banner where the seed is written.
+const SEED_LINE: u16 = 6;
+/// Number of best entries shown in the leaderboard.
+const LEADERBOARD_ROWS: usize = 5;
+/// The header, the best entries and the new entry when it is not one of them.
+const LEADERBOARD_HEIGHT: u16 = 1 + LEADERBOARD_ROWS as u16 + 1;
 
+/// Title of the banner, depending on how the round ended.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Banner {
+    #[default]
+    GameOver,
+    /// The snake filled the board.
+    Victory,
+    Winner(Player),
+    /// The snake of the computer outlived the player, or got the best score.
+    ComputerWins,
+    /// Both snakes died at the same time, or filled the board with the same score.
+    Draw,
+}
+
+impl Banner {
+    /// Banner of a round that is over, a round against an opponent always has a winner or a draw.
+    pub fn of(state: &GameState) -> Self {
+        match (state.second_snake(), state.winner()) {
+            (Some(_), Some(Player::Two)) if state.opponent() == Opponent::Computer => {
+                Banner::ComputerWins
+            }
+            (Some(_), Some(player)) => Banner::Winner(player),
+            (Some(_), None) => Banner::Draw,
+            (None, _) if state.is_won() => Banner::Victory,
+            (None, _) => Banner::GameOver,
+        }
+    }
+
+    fn lines(&self) -> [&'static str; BANNER_HEIGHT as usize] {
+        match self {
+            Banner::GameOver => GAME_OVER,
+            Banner::Victory => VICTORY,
+            Banner::Winner(Player::One) => PLAYER_ONE_WINS,
+            Banner::Winner(Player::Two) => PLAYER_TWO_WINS,
+            Banner::ComputerWins => COMPUTER_WINS,
+            Banner::Draw => DRAW,
+        }
+    }
+}
+
+/// Banner shown when the game is over.
+///
+/// The cells under the banner are kept to restore them when it is erased.
 #[derive(Accessors)]
-pub struct GameOverComponent {
-    screen: SharedScreen<Stdout>,
+pub struct GameOverComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
     #[accessors(get_copy)]
     position: Position,
+    hidden_cells: Option<HiddenCells>,
+    /// Seed of the round, shown so it can be played again.
+    #[accessors(get_copy, set)]
+    seed: u64,
+    #[accessors(get_copy, set)]
+    banner: Banner,
+    /// Best high scores, nothing is shown when it is empty.
+    leaderboard: LeaderboardComponent<T>,
 }
 
-impl GameOverComponent {
-    pub fn new(screen: SharedScreen<Stdout>, position: Position) -> Self {
-        Self { screen, position }
+impl<T: Write + Send> GameOverComponent<T> {
+    pub fn new(screen: SharedScreen<T>, position: Position) -> Self {
+        Self {
+            screen: SharedScreen::clone(&screen),
+            position,
+            hidden_cells: None,
+            seed: 0,
+            banner: Banner::GameOver,
+            leaderboard: LeaderboardComponent::new(
+                screen,
+                leaderboard_position(position),
+                GAME_OVER_WIDTH,
+            ),
+        }
     }
 
-    pub fn render(&self) -> super::Result<()> {
-        let mut screen = self.screen.lock()?;
-        let Position { x, y } = self.position;
-        for (i, y) in (y..y + GAME_OVER_HEIGHT).enumerate() {
-            screen.cursor_mut().move_to(x, y)?;
-            screen.write_str(GAME_OVER[i])?;
+    /// Show the best high scores with the settings of the new one under the banner,
+    /// with the new one highlighted.
+    ///
+    /// The new high score is added at the end when it is not one of the best.
+    pub fn set_leaderboard(
+        &mut self,
+        high_scores: &HighScores,
+        new_high_score: HighScore,
+        new_rank: usize,
+    ) {
+        let mut rows: Vec<_> = high_scores
+            .with_settings(new_high_score.settings())
+            .take(LEADERBOARD_ROWS)
+            .cloned()
+            .enumerate()
+            .collect();
+        if new_rank >= LEADERBOARD_ROWS {
+            rows.push((new_rank, new_high_score));
+        }
+        self.leaderboard.set_rows(rows, Some(new_rank));
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.hidden_cells.is_some()
+    }
+
+    /// Move the banner without restoring what was under it.
+    ///
+    /// Used when the screen was drawn again, so the hidden cells are no longer valid.
+    pub fn set_position(&mut self, position: Position) -> super::Result<()> {
+        self.position = position;
+        self.leaderboard
+            .set_position(leaderboard_position(position));
+        if self.hidden_cells.take().is_some() {
+            self.render()?;
         }
         Ok(())
     }
 
-    pub fn erase(&self) -> super::Result<()> {
+    pub fn render(&mut self) -> super::Result<()> {
         let mut screen = self.screen.lock()?;
         let Position { x, y } = self.position;
-        for y in y..y + GAME_OVER_HEIGHT {
-            screen.cursor_mut().move_to(x, y)?;
-            screen.write_str(&" ".repeat(GAME_OVER_WIDTH.into()))?;
+        if self.hidden_cells.is_none() {
+            let boundary = Boundary::new(
+                self.position,
+                Dimension::new(GAME_OVER_WIDTH, GAME_OVER_HEIGHT),
+            );
+            self.hidden_cells = Some(HiddenCells::capture(&screen, boundary));
+        }
+        let banner = self.banner.lines();
+        for (i, y) in (y..y + BANNER_HEIGHT).enumerate() {
+            screen.draw_str(x, y, banner[i], &Style::new());
+        }
+        let seed = format!("Seed: {}", self.seed);
+        screen.draw_str(
+            x + (GAME_OVER_WIDTH - seed.len() as u16) / 2,
+            y + SEED_LINE,
+            &seed,
+            &Style::from([StyleProperty::Dim]),
+        );
+
+        if !self.leaderboard.is_empty() {
+            let blank = " ".repeat(GAME_OVER_WIDTH as usize);
+            screen.draw_str(x, y + BANNER_HEIGHT, &blank, &Style::new());
+            drop(screen);
+            self.leaderboard.render()?;
+        }
+        Ok(())
+    }
+
+    pub fn erase(&mut self) -> super::Result<()> {
+        if let Some(hidden_cells) = self.hidden_cells.take() {
+            let mut screen = self.screen.lock()?;
+            hidden_cells.restore(&mut screen);
         }
         Ok(())
     }
 }
 
-impl Drop for GameOverComponent {
+/// The leaderboard is under the banner, after an empty line.
+fn leaderboard_position(position: Position) -> Position {
+    Position::new(position.x, position.y + BANNER_HEIGHT + 1)
+}
+
+impl<T: Write + Send> Drop for GameOverComponent<T> {
     fn drop(&mut self) {
         let _ = self.erase();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Banner, GameOverComponent, GAME_OVER, GAME_OVER_WIDTH, VICTORY};
+    use crate::component::{virtual_screen, Position};
+
+    fn render(banner: Banner, seed: u64) -> Vec<String> {
+        let (screen, terminal) = virtual_screen(GAME_OVER_WIDTH + 2, 10);
+        let mut game_over = GameOverComponent::new(screen.clone(), Position::new(2, 2));
+        game_over.set_banner(banner);
+        game_over.set_seed(seed);
+        game_over.render().unwrap();
+        screen.lock().unwrap().present().unwrap();
+        terminal.contents().lines().map(str::to_string).collect()
+    }
+
+    /// Line of the banner as drawn from the second column, without trailing whitespace.
+    fn drawn(line: &str) -> String {
+        format!(" {line}").trim_end().to_string()
+    }
+
+    #[test]
+    fn render_game_over_banner_with_the_seed() {
+        let lines = render(Banner::GameOver, 42);
+
+        assert_eq!(lines[0], "");
+        for (i, line) in GAME_OVER.iter().enumerate().filter(|(i, _)| *i != 6) {
+            assert_eq!(lines[i + 1], drawn(line));
+        }
+        assert_eq!(lines[7].trim(), "Seed: 42");
+        assert_eq!(
+            lines[7].find('S'),
+            Some(1 + (GAME_OVER_WIDTH as usize - 8) / 2)
+        );
+    }
+
+    #[test]
+    fn render_victory_banner() {
+        let lines = render(Banner::Victory, 7);
+
+        for (i, line) in VICTORY.iter().enumerate().take(6) {
+            assert_eq!(lines[i + 1], drawn(line));
+        }
+    }
+
+    #[test]
+    fn restore_the_cells_under_the_banner() {
+        let (screen, terminal) = virtual_screen(GAME_OVER_WIDTH, 8);
+        screen
+            .lock()
+            .unwrap()
+            .draw_str(1, 1, "under", &Default::default());
+        let mut game_over = GameOverComponent::new(screen.clone(), Position::new(1, 1));
+        game_over.render().unwrap();
+        game_over.erase().unwrap();
+        screen.lock().unwrap().present().unwrap();
+
+        assert_eq!(terminal.contents().lines().next(), Some("under"));
+        assert!(!game_over.is_visible());
+    }
+}
@@ -0,0 +1,103 @@
+use std::io::{Stdout, Write};
+
+use accessors_rs::Accessors;
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Style, StyleProperty},
+};
+
+use crate::high_score::HighScore;
+
+use super::Position;
+
+/// Width of a row without padding.
+pub const LEADERBOARD_WIDTH: u16 = 62;
+
+/// Table of high scores with their rank, one of them can be highlighted.
+#[derive(Accessors)]
+pub struct LeaderboardComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    #[accessors(get_copy)]
+    position: Position,
+    /// Rows are centered in this width, so they cover what is under the table.
+    width: u16,
+    rows: Vec<(usize, HighScore)>,
+    highlighted: Option<usize>,
+    /// Line above the header, like the settings of the rounds.
+    #[accessors(set)]
+    caption: Option<String>,
+}
+
+impl<T: Write + Send> LeaderboardComponent<T> {
+    pub fn new(screen: SharedScreen<T>, position: Position, width: u16) -> Self {
+        Self {
+            screen,
+            position,
+            width: width.max(LEADERBOARD_WIDTH),
+            rows: Vec::new(),
+            highlighted: None,
+            caption: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    /// Show the high scores with their rank, the one with the highlighted rank stand out.
+    pub fn set_rows(&mut self, rows: Vec<(usize, HighScore)>, highlighted: Option<usize>) {
+        self.rows = rows;
+        self.highlighted = highlighted;
+    }
+
+    /// Number of lines drawn, with the header and the caption.
+    pub fn height(&self) -> u16 {
+        self.rows.len() as u16 + 1 + self.caption.is_some() as u16
+    }
+
+    pub fn render(&self) -> super::Result<()> {
+        let mut screen = self.screen.lock()?;
+        let Position { x, mut y } = self.position;
+        if let Some(caption) = &self.caption {
+            let caption = format!("{caption:^width$}", width = self.width as usize);
+            screen.draw_str(x, y, &caption, &Style::from([StyleProperty::Dim]));
+            y += 1;
+        }
+        let header = self.row("Rank", "Score", "Length", "Time", "Date", "Seed");
+        screen.draw_str(x, y, &header, &Style::from([StyleProperty::Underline]));
+        for (i, (rank, high_score)) in self.rows.iter().enumerate() {
+            let row = self.row(
+                &format!("{}.", rank + 1),
+                &high_score.score().to_string(),
+                &high_score.length().to_string(),
+                &high_score.elapsed_text(),
+                &high_score.date_text(),
+                &high_score.seed().to_string(),
+            );
+            let style = if Some(*rank) == self.highlighted {
+                Style::from([StyleProperty::Bold, StyleProperty::Invert])
+            } else {
+                Style::new()
+            };
+            screen.draw_str(x, y + 1 + i as u16, &row, &style);
+        }
+        Ok(())
+    }
+
+    fn row(
+        &self,
+        rank: &str,
+        score: &str,
+        length: &str,
+        time: &str,
+        date: &str,
+        seed: &str,
+    ) -> String {
+        let row = format!("{rank:>4}  {score:>7}  {length:>6}  {time:>5}  {date:<10}  {seed:<20}");
+        format!("{row:^width$}", width = self.width as usize)
+    }
+}
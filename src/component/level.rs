@@ -0,0 +1,50 @@
+use std::io::{Stdout, Write};
+
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Style, StyleProperty},
+};
+
+use super::Position;
+
+/// Level of the round, shown next to the score.
+pub struct LevelComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    /// Last level rendered.
+    level: u32,
+    position: Position,
+    style: Style,
+}
+
+impl<T: Write + Send> LevelComponent<T> {
+    pub fn new(screen: SharedScreen<T>, position: Position) -> Self {
+        Self {
+            screen,
+            position,
+            level: 1,
+            style: Style::from([StyleProperty::Dim]),
+        }
+    }
+
+    /// Render the level at the position, the previous one is erased first since it can move.
+    pub fn render(&mut self, position: Position, level: u32) -> super::Result<()> {
+        self.erase()?;
+        self.position = position;
+        self.level = level;
+        let Position { x, y } = self.position;
+        let mut screen = self.screen.lock()?;
+        screen.draw_str(x, y, &self.text(), &self.style);
+        Ok(())
+    }
+
+    fn text(&self) -> String {
+        format!("Level: {}", self.level)
+    }
+
+    fn erase(&self) -> super::Result<()> {
+        let Position { x, y } = self.position;
+        let mut screen = self.screen.lock()?;
+        screen.draw_str(x, y, &" ".repeat(self.text().len()), &Style::new());
+        Ok(())
+    }
+}
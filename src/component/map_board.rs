@@ -0,0 +1,75 @@
+use std::io::{Stdout, Write};
+
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Color, Style, StyleProperty},
+};
+
+use crate::{
+    game_state::snake::{Snake, SNAKE_START_LENGTH},
+    map::Map,
+};
+
+use super::{
+    apple::AppleComponent, snake::SnakeComponent, walls::WallsComponent, Boundary, Position,
+};
+
+/// Map being edited, drawn like a game board at the start of a round with a cursor over it.
+pub struct MapBoardComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    walls: WallsComponent<T>,
+    apple: AppleComponent<T>,
+    snake_component: SnakeComponent<T>,
+    /// Style of the start of a snake that does not fit in the map.
+    blocked_style: Style,
+}
+
+impl<T: Write + Send> MapBoardComponent<T> {
+    pub fn new(screen: SharedScreen<T>) -> Self {
+        Self {
+            walls: WallsComponent::new(SharedScreen::clone(&screen)),
+            apple: AppleComponent::new(SharedScreen::clone(&screen)),
+            snake_component: SnakeComponent::new(SharedScreen::clone(&screen)),
+            screen,
+            blocked_style: Style::from([
+                StyleProperty::Bold,
+                StyleProperty::Color(Color::RGB(235, 35, 55)),
+            ]),
+        }
+    }
+
+    /// Draw the map on the board, the cursor is relative to the top left cell of the map.
+    pub fn render(&self, map: &Map, board: Boundary, cursor: Position) -> super::Result<()> {
+        self.erase(board)?;
+        self.walls.render(&map.walls_on(board), board)?;
+        for spawn in map.apple_spawns_on(board) {
+            self.apple.render(spawn)?;
+        }
+        let mut screen = if map.can_hold_snake() {
+            self.snake_component
+                .render(&Snake::new(map.start_on(board), SNAKE_START_LENGTH))?;
+            self.screen.lock()?
+        } else {
+            // Only the start is drawn when the snake would go through a wall or off the map.
+            let mut screen = self.screen.lock()?;
+            let Position { x, y } = map.start_on(board).position();
+            screen.draw_char(x, y, '✕', &self.blocked_style);
+            screen
+        };
+        let Position { x, y } = board.position() + cursor;
+        let cell = screen.buffer().get(x, y).cloned().unwrap_or_default();
+        let mut style = Style::from(cell.style());
+        style.add_property(StyleProperty::Invert);
+        screen.draw_char(x, y, cell.symbol(), &style);
+        Ok(())
+    }
+
+    fn erase(&self, board: Boundary) -> super::Result<()> {
+        let mut screen = self.screen.lock()?;
+        let blank = " ".repeat(board.dimension().width as usize);
+        for y in board.top()..=board.bottom() {
+            screen.draw_str(board.left(), y, &blank, &Style::new());
+        }
+        Ok(())
+    }
+}
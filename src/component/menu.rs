@@ -0,0 +1,87 @@
+use std::io::{Stdout, Write};
+
+use accessors_rs::Accessors;
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Color, Style, StyleProperty},
+};
+
+use super::{Dimension, Position};
+
+/// Space kept on each side of the items for the selection marks.
+const MARGIN: u16 = 4;
+
+/// Vertical list of items navigable with the keyboard, one of them is selected.
+#[derive(Accessors)]
+pub struct MenuComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    style: Style,
+    selected_style: Style,
+    #[accessors(get_copy)]
+    position: Position,
+    items: Vec<String>,
+    #[accessors(get_copy)]
+    selected: usize,
+}
+
+impl<T: Write + Send> MenuComponent<T> {
+    pub fn new(screen: SharedScreen<T>, position: Position, items: Vec<String>) -> Self {
+        Self {
+            screen,
+            position,
+            items,
+            selected: 0,
+            style: Style::new(),
+            selected_style: Style::from([
+                StyleProperty::Bold,
+                StyleProperty::Color(Color::RGB(83, 134, 66)),
+            ]),
+        }
+    }
+
+    /// Width of the longest item with its selection marks, and one line per item.
+    pub fn dimension(&self) -> Dimension {
+        menu_dimension(&self.items)
+    }
+
+    /// Select the item at the index, or the last one when the index is past the end.
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.items.len().max(1);
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self
+            .selected
+            .checked_sub(1)
+            .unwrap_or(self.items.len().saturating_sub(1));
+    }
+
+    pub fn render(&self) -> super::Result<()> {
+        let mut screen = self.screen.lock()?;
+        let Position { x, y } = self.position;
+        let width = self.dimension().width as usize;
+        for (i, item) in self.items.iter().enumerate() {
+            let (text, style) = if i == self.selected {
+                (format!("▸ {item} ◂"), &self.selected_style)
+            } else {
+                (item.clone(), &self.style)
+            };
+            screen.draw_str(x, y + i as u16, &format!("{text:^width$}"), style);
+        }
+        Ok(())
+    }
+}
+
+/// Dimension of a menu with the items.
+pub fn menu_dimension(items: &[String]) -> Dimension {
+    let width = items
+        .iter()
+        .map(|item| item.chars().count() as u16)
+        .max()
+        .unwrap_or_default();
+    Dimension::new(width + MARGIN, items.len() as u16)
+}
@@ -0,0 +1,78 @@
+use std::io::{Stdout, Write};
+
+use accessors_rs::Accessors;
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Style, StyleProperty},
+};
+
+use super::{Boundary, Dimension, HiddenCells, Position};
+
+pub const PAUSE_WIDTH: u16 = 22;
+pub const PAUSE_HEIGHT: u16 = 5;
+const PAUSE: [&str; PAUSE_HEIGHT as usize] = [
+    "╭────────────────────╮",
+    "│       PAUSED       │",
+    "│                    │",
+    "│  Press p to resume │",
+    "╰────────────────────╯",
+];
+
+/// Overlay shown while the game is paused.
+///
+/// The cells under the overlay are kept to restore them when it is erased.
+#[derive(Accessors)]
+pub struct PauseComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    style: Style,
+    #[accessors(get_copy)]
+    position: Position,
+    hidden_cells: Option<HiddenCells>,
+}
+
+impl<T: Write + Send> PauseComponent<T> {
+    pub fn new(screen: SharedScreen<T>, position: Position) -> Self {
+        Self {
+            screen,
+            position,
+            hidden_cells: None,
+            style: Style::from([StyleProperty::Bold]),
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.hidden_cells.is_some()
+    }
+
+    /// Move the overlay without restoring what was under it.
+    ///
+    /// Used when the screen was drawn again, so the hidden cells are no longer valid.
+    pub fn set_position(&mut self, position: Position) -> super::Result<()> {
+        self.position = position;
+        if self.hidden_cells.take().is_some() {
+            self.render()?;
+        }
+        Ok(())
+    }
+
+    pub fn render(&mut self) -> super::Result<()> {
+        let mut screen = self.screen.lock()?;
+        let Position { x, y } = self.position;
+        if self.hidden_cells.is_none() {
+            let boundary = Boundary::new(self.position, Dimension::new(PAUSE_WIDTH, PAUSE_HEIGHT));
+            self.hidden_cells = Some(HiddenCells::capture(&screen, boundary));
+        }
+        for (i, y) in (y..y + PAUSE_HEIGHT).enumerate() {
+            screen.draw_str(x, y, PAUSE[i], &self.style);
+        }
+        Ok(())
+    }
+
+    pub fn erase(&mut self) -> super::Result<()> {
+        if let Some(hidden_cells) = self.hidden_cells.take() {
+            let mut screen = self.screen.lock()?;
+            hidden_cells.restore(&mut screen);
+        }
+        Ok(())
+    }
+}
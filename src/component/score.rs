@@ -1,4 +1,4 @@
-use std::io::Stdout;
+use std::io::{Stdout, Write};
 
 use snake_in_terminal::terminus::{
     screen::SharedScreen,
@@ -7,37 +7,58 @@ use snake_in_terminal::terminus::{
 
 use super::Position;
 
-pub struct ScoreComponent {
-    screen: SharedScreen<Stdout>,
+/// Score of a player with a label in front of it.
+pub struct ScoreComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    /// Last label and score rendered.
+    label: &'static str,
     score: u32,
     position: Position,
     style: Style,
 }
 
-impl ScoreComponent {
-    pub fn try_new(screen: SharedScreen<Stdout>, position: Position) -> super::Result<Self> {
-        let score = Self {
+impl<T: Write + Send> ScoreComponent<T> {
+    pub fn new(screen: SharedScreen<T>, position: Position) -> Self {
+        Self {
             screen,
             position,
+            label: "Score",
             score: 0,
             style: Style::from([StyleProperty::Dim]),
-        };
-        score.render()?;
-        Ok(score)
+        }
     }
 
-    pub fn add(&mut self, add: u32) -> super::Result<()> {
-        self.score += add;
-        self.render()
+    /// Render the score at the position, the previous one is erased first since it can be
+    /// longer or move.
+    pub fn render(
+        &mut self,
+        position: Position,
+        label: &'static str,
+        score: u32,
+    ) -> super::Result<()> {
+        self.erase()?;
+        self.position = position;
+        self.label = label;
+        self.score = score;
+        let Position { x, y } = self.position;
+        let mut screen = self.screen.lock()?;
+        screen.draw_str(x, y, &self.text(), &self.style);
+        Ok(())
+    }
+
+    /// Number of columns taken by the last score rendered.
+    pub fn width(&self) -> u16 {
+        self.text().len() as u16
+    }
+
+    fn text(&self) -> String {
+        format!("{}: {}", self.label, self.score)
     }
 
-    fn render(&self) -> super::Result<()> {
+    fn erase(&self) -> super::Result<()> {
         let Position { x, y } = self.position;
         let mut screen = self.screen.lock()?;
-        screen.cursor_mut().move_to(x, y)?;
-        screen.write_str(self.style.ansi_sequence().as_str())?;
-        screen.write_str(&format!("Score: {}", self.score))?;
-        screen.write_str(Style::RESET)?;
+        screen.draw_str(x, y, &" ".repeat(self.text().len()), &Style::new());
         Ok(())
     }
 }
@@ -1,227 +1,127 @@
-use std::{
-    collections::{HashSet, LinkedList},
-    io::Stdout,
-};
-
-use accessors_rs::Accessors;
+use std::io::{Stdout, Write};
 
 use snake_in_terminal::terminus::{
     screen::SharedScreen,
     style::{Color, Style, StyleProperty},
 };
 
-use super::Position;
+use crate::game_state::snake::{Direction, Snake};
 
-#[derive(Accessors)]
-pub struct SnakeComponent {
-    screen: SharedScreen<Stdout>,
+pub struct SnakeComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
     head_style: Style,
     body_style: Style,
-    #[accessors(get, get_mut)]
-    snake: Snake,
 }
 
-impl SnakeComponent {
-    pub fn try_new(screen: SharedScreen<Stdout>, position: Position) -> super::Result<Self> {
-        let snake = Self {
-            screen,
-            snake: Snake::new(SnakeNode::new(position, Direction::Right), 10),
-            head_style: Style::from([
-                StyleProperty::Color(Color::RGB(83, 134, 66)),
-                StyleProperty::Bold,
-            ]),
-            body_style: Style::from([StyleProperty::Color(Color::RGB(184, 195, 52))]),
-        };
-        snake.render()?;
-        Ok(snake)
-    }
-
-    fn render(&self) -> super::Result<()> {
-        let mut screen = self.screen.lock()?;
-
-        let last_idx = self.snake.nodes.len() - 1;
-
-        let mut previous_body_direction = self.snake.head().direction;
-
-        for (
-            i,
-            SnakeNode {
-                position,
-                direction,
-            },
-        ) in self.snake.nodes.iter().enumerate()
-        {
-            screen.cursor_mut().move_to(position.x, position.y)?;
-            if i == 0 {
-                screen.write_str(self.head_style.ansi_sequence().as_str())?;
-            } else if i == 1 {
-                screen.write_str(self.body_style.ansi_sequence().as_str())?;
-            }
-            if i == last_idx {
-                screen.write_str(match previous_body_direction {
-                    Direction::Up => "╿",
-                    Direction::Down => "╽",
-                    Direction::Left => "╾",
-                    Direction::Right => "╼",
-                })?;
-                screen.write_str(Style::RESET)?;
-            } else {
-                screen.write_str(match (direction, previous_body_direction) {
-                    (Direction::Up | Direction::Down, Direction::Up | Direction::Down) => "║",
-                    (Direction::Left | Direction::Right, Direction::Left | Direction::Right) => "═",
-                    (Direction::Up, Direction::Left) | (Direction::Right, Direction::Down) => "╗",
-                    (Direction::Up, Direction::Right) | (Direction::Left, Direction::Down) => "╔",
-                    (Direction::Down, Direction::Left) | (Direction::Right, Direction::Up) => "╝",
-                    (Direction::Down, Direction::Right) | (Direction::Left, Direction::Up) => "╚",
-                })?;
-            }
-            previous_body_direction = direction.clone();
-        }
-        Ok(())
-    }
-
-    fn erase(&self) -> super::Result<()> {
-        let mut screen = self.screen.lock()?;
-        for SnakeNode {
-            position: Position { x, y },
-            ..
-        } in &self.snake.nodes
-        {
-            screen.cursor_mut().move_to(*x, *y)?;
-            screen.write_str(" ")?;
-        }
-        Ok(())
+impl<T: Write + Send> SnakeComponent<T> {
+    pub fn new(screen: SharedScreen<T>) -> Self {
+        Self::with_colors(screen, Color::RGB(83, 134, 66), Color::RGB(184, 195, 52))
     }
 
-    pub fn move_forward(&mut self) -> super::Result<()> {
-        if let Some(old_tail) = self.snake.move_forward() {
-            self.erase_snake_node(old_tail)?;
-            self.render()?;
+    /// Snake drawn with other colors, to tell it apart from the first one.
+    pub fn with_colors(screen: SharedScreen<T>, head: Color, body: Color) -> Self {
+        Self {
+            screen,
+            head_style: Style::from([StyleProperty::Color(head), StyleProperty::Bold]),
+            body_style: Style::from([StyleProperty::Color(body)]),
         }
-        Ok(())
     }
 
-    fn erase_snake_node(&self, node: SnakeNode) -> super::Result<()> {
+    /// Draw each node with a glyph joining it to the node in front of it.
+    ///
+    /// The glyphs only depend on the direction of the nodes, not on their position,
+    /// so the body stay connected when it wrap around the board.
+    pub fn render(&self, snake: &Snake) -> super::Result<()> {
         let mut screen = self.screen.lock()?;
-        let Position { x, y } = node.position;
-        screen.cursor_mut().move_to(x, y)?;
-        screen.write_str(" ")?;
-        Ok(())
-    }
-}
 
-pub struct Snake {
-    direction: Direction,
-    nodes: LinkedList<SnakeNode>,
-}
-
-impl Snake {
-    fn new(head: SnakeNode, len: u16) -> Snake {
-        let nodes = LinkedList::from([head]);
-        let direction = Direction::Right;
-        let mut snake = Self { direction, nodes };
-        for _ in 0..len {
-            snake.add_node_at_the_front()
-        }
-        snake
-    }
+        let last_idx = snake.nodes().len() - 1;
 
-    pub fn head(&self) -> &SnakeNode {
-        self.nodes.front().expect("Snake need at least one node.")
-    }
-
-    pub fn tail(&self) -> &SnakeNode {
-        self.nodes.back().expect("Snake need at least one node.")
-    }
+        let mut previous_body_direction = snake.head().direction();
 
-    pub fn eat(&mut self) {
-        self.add_node_at_the_back()
-    }
-
-    pub fn change_direction(&mut self, direction: Direction) -> bool {
-        let head_direction = self.head().direction;
-        let has_change = match direction {
-            Direction::Up => head_direction != Direction::Down,
-            Direction::Down => head_direction != Direction::Up,
-            Direction::Left => head_direction != Direction::Right,
-            Direction::Right => head_direction != Direction::Left,
-        };
-        if has_change {
-            self.direction = direction;
-        }
-        has_change
-    }
-
-    fn move_forward(&mut self) -> Option<SnakeNode> {
-        self.add_node_at_the_front();
-        self.nodes.pop_back()
-    }
-
-    pub fn is_biting_itself(&self) -> bool {
-        let mut p_map = HashSet::new();
-        for SnakeNode { position, .. } in &self.nodes {
-            if !p_map.insert(position) {
-                return true;
-            }
-        }
-        false
-    }
-
-    pub fn get_next_position(&self) -> Position {
-        let mut position = self.head().position;
-        match self.direction {
-            Direction::Up => position.y -= 1,
-            Direction::Down => position.y += 1,
-            Direction::Left => position.x -= 1,
-            Direction::Right => position.x += 1,
-        }
-        position
-    }
-
-    fn add_node_at_the_front(&mut self) {
-        self.nodes
-            .push_front(SnakeNode::new(self.get_next_position(), self.direction));
-    }
-
-    fn add_node_at_the_back(&mut self) {
-        let mut position = self.tail().position;
-        let direction = self.tail().direction;
-        match direction {
-            Direction::Up => position.y += 1,
-            Direction::Down => position.y -= 1,
-            Direction::Left => position.x += 1,
-            Direction::Right => position.x -= 1,
-        }
-        self.nodes.push_back(SnakeNode::new(position, direction))
-    }
-}
-
-#[derive(Accessors)]
-#[accessors(get_copy)]
-pub struct SnakeNode {
-    position: Position,
-    direction: Direction,
-}
-
-impl SnakeNode {
-    fn new(position: Position, direction: Direction) -> Self {
-        Self {
-            position,
-            direction,
+        for (i, node) in snake.nodes().iter().enumerate() {
+            let (position, direction) = (node.position(), node.direction());
+            let style = if i == 0 {
+                &self.head_style
+            } else {
+                &self.body_style
+            };
+            let glyph = if i == last_idx {
+                match previous_body_direction {
+                    Direction::Up => '╿',
+                    Direction::Down => '╽',
+                    Direction::Left => '╾',
+                    Direction::Right => '╼',
+                }
+            } else {
+                match (direction, previous_body_direction) {
+                    (Direction::Up | Direction::Down, Direction::Up | Direction::Down) => '║',
+                    (Direction::Left | Direction::Right, Direction::Left | Direction::Right) => '═',
+                    (Direction::Up, Direction::Left) | (Direction::Right, Direction::Down) => '╗',
+                    (Direction::Up, Direction::Right) | (Direction::Left, Direction::Down) => '╔',
+                    (Direction::Down, Direction::Left) | (Direction::Right, Direction::Up) => '╝',
+                    (Direction::Down, Direction::Right) | (Direction::Left, Direction::Up) => '╚',
+                }
+            };
+            screen.draw_char(position.x, position.y, glyph, style);
+            previous_body_direction = direction;
         }
+        Ok(())
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Drop for SnakeComponent {
-    fn drop(&mut self) {
-        let _ = self.erase();
+#[cfg(test)]
+mod tests {
+    use snake_in_terminal::terminus::style::{Color, Style, StyleProperty};
+
+    use super::SnakeComponent;
+    use crate::{
+        component::{virtual_screen, Position},
+        game_state::snake::{Direction, Snake, SnakeNode},
+    };
+
+    fn render(snake: &Snake) -> String {
+        let (screen, terminal) = virtual_screen(6, 4);
+        SnakeComponent::new(screen.clone()).render(snake).unwrap();
+        screen.lock().unwrap().present().unwrap();
+        terminal.contents()
+    }
+
+    #[test]
+    fn render_straight_snake() {
+        let snake = Snake::new(SnakeNode::new(Position::new(2, 2), Direction::Right), 3);
+        assert_eq!(render(&snake), "\n ╼═══\n\n\n");
+    }
+
+    #[test]
+    fn render_corners_toward_the_head() {
+        let mut snake = Snake::new(SnakeNode::new(Position::new(2, 1), Direction::Right), 3);
+        snake.change_direction(Direction::Down);
+        snake.move_forward();
+        snake.move_forward();
+        snake.change_direction(Direction::Left);
+        snake.move_forward();
+        assert_eq!(render(&snake), "    ╽\n    ║\n   ═╝\n\n");
+    }
+
+    #[test]
+    fn render_head_bold() {
+        let (screen, terminal) = virtual_screen(6, 4);
+        let snake = Snake::new(SnakeNode::new(Position::new(2, 2), Direction::Right), 3);
+        SnakeComponent::new(screen.clone()).render(&snake).unwrap();
+        screen.lock().unwrap().present().unwrap();
+
+        let head = terminal.cell(5, 2).unwrap();
+        let body = terminal.cell(4, 2).unwrap();
+        assert_eq!(
+            head.style(),
+            &Style::from([
+                StyleProperty::Color(Color::RGB(83, 134, 66)),
+                StyleProperty::Bold,
+            ])
+        );
+        assert_eq!(
+            body.style(),
+            &Style::from([StyleProperty::Color(Color::RGB(184, 195, 52))])
+        );
     }
 }
@@ -0,0 +1,35 @@
+use std::io::{Stdout, Write};
+
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Style, StyleProperty},
+};
+
+/// Line over the whole width of the screen, with a text on its left and another on its right.
+pub struct StatusBarComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    style: Style,
+    y: u16,
+}
+
+impl<T: Write + Send> StatusBarComponent<T> {
+    pub fn new(screen: SharedScreen<T>, y: u16) -> Self {
+        Self {
+            screen,
+            y,
+            style: Style::from([StyleProperty::Invert]),
+        }
+    }
+
+    /// Draw the bar, the left text is cut first when the screen is too narrow.
+    pub fn render(&self, left: &str, right: &str) -> super::Result<()> {
+        let mut screen = self.screen.lock()?;
+        let width = screen.size().0 as usize;
+        let right: String = right.chars().take(width).collect();
+        let space = width - right.chars().count();
+        let left: String = left.chars().take(space).collect();
+        let line = format!("{left:<space$}{right}");
+        screen.draw_str(1, self.y, &line, &self.style);
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
-use std::{io::Stdout, sync::Weak, time::Duration};
+use std::{
+    io::{Stdout, Write},
+    sync::Weak,
+    time::Duration,
+};
 
 use accessors_rs::Accessors;
-use tokio::{sync::Mutex, task::JoinHandle};
+use tokio::{sync::Mutex, task::JoinHandle, time::Instant};
 
 use snake_in_terminal::terminus::{
     screen::SharedScreen,
@@ -10,47 +14,82 @@ use snake_in_terminal::terminus::{
 
 use super::Position;
 
-pub const LABEL: &'static str = "Timer: ";
+pub const LABEL: &str = "Timer: ";
 
 #[derive(Accessors)]
-pub struct TimerComponent {
-    screen: SharedScreen<Stdout>,
+pub struct TimerComponent<T: Write + Send + 'static = Stdout> {
+    screen: SharedScreen<T>,
     style: Style,
     timer_handle: Option<JoinHandle<super::Result<()>>>,
     position: Position,
-    seconds: u32,
+    /// Time counted before the timer was last started.
+    elapsed: Duration,
+    running_since: Option<Instant>,
 }
 
-impl TimerComponent {
-    pub fn new(screen: SharedScreen<Stdout>, position: Position) -> Self {
+impl<T: Write + Send + 'static> TimerComponent<T> {
+    pub fn new(screen: SharedScreen<T>, position: Position) -> Self {
         Self {
             screen,
             position,
-            seconds: 0,
+            elapsed: Duration::ZERO,
+            running_since: None,
             timer_handle: None,
             style: Style::from([StyleProperty::Dim]),
         }
     }
 
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+            + self
+                .running_since
+                .map(|since| since.elapsed())
+                .unwrap_or_default()
+    }
+
     fn text(&self) -> String {
-        let min = self.seconds / 60;
-        let sec = self.seconds - (min * 60);
+        let seconds = self.elapsed().as_secs();
+        let min = seconds / 60;
+        let sec = seconds - (min * 60);
         format!("{LABEL}{:02}:{:02}", min, sec)
     }
 
     pub fn render(&self) -> super::Result<()> {
         let Position { x, y } = self.position;
         let mut screen = self.screen.lock()?;
-        screen.cursor_mut().move_to(x, y)?;
-        screen.write_str(&self.style.prettify(&self.text()))?;
+        screen.draw_str(x, y, &self.text(), &self.style);
         Ok(())
     }
 
+    pub fn set_position(&mut self, position: Position) -> super::Result<()> {
+        self.erase()?;
+        self.position = position;
+        self.render()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.timer_handle.is_some()
+    }
+
+    /// Stop the timer and count from zero at the next start.
+    pub fn reset(&mut self) -> super::Result<()> {
+        self.stop_timer();
+        self.erase()?;
+        self.elapsed = Duration::ZERO;
+        self.render()
+    }
+
+    /// Stop counting, the elapsed time is kept until the timer is started again.
+    pub fn stop_timer(&mut self) {
+        self.elapsed = self.elapsed();
+        self.running_since = None;
+        self.abort_handle();
+    }
+
     fn erase(&self) -> super::Result<()> {
         let Position { x, y } = self.position;
         let mut screen = self.screen.lock()?;
-        screen.cursor_mut().move_to(x, y)?;
-        screen.write_str(&" ".repeat(self.text().len()))?;
+        screen.draw_str(x, y, &" ".repeat(self.text().len()), &Style::new());
         Ok(())
     }
 
@@ -60,17 +99,26 @@ impl TimerComponent {
         }
     }
 
+    /// Count from the time elapsed before the timer was stopped.
     pub async fn start_timer(timer: Weak<Mutex<Self>>) {
         let weak_timer = Weak::clone(&timer);
+        let Some(elapsed) = weak_timer.upgrade() else {
+            return;
+        };
+        let elapsed = elapsed.lock().await.elapsed();
+        let now = Instant::now();
+        // Render when the next second is reached, not one second after the start.
+        let period = Duration::from_secs(1);
+        let first_tick = now + period - Duration::from_nanos(elapsed.subsec_nanos().into());
 
         let handle = tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(1));
+            let mut interval = tokio::time::interval_at(first_tick, period);
             loop {
                 interval.tick().await;
                 if let Some(timer) = timer.upgrade() {
-                    let mut timer = timer.lock().await;
-                    timer.seconds += 1;
+                    let timer = timer.lock().await;
                     timer.render()?;
+                    timer.screen.lock()?.present()?;
                 } else {
                     break;
                 }
@@ -80,13 +128,14 @@ impl TimerComponent {
 
         if let Some(timer) = weak_timer.upgrade() {
             let mut timer = timer.lock().await;
-            timer.abort_handle();
+            timer.stop_timer();
+            timer.running_since = Some(now);
             timer.timer_handle.replace(handle);
         }
     }
 }
 
-impl Drop for TimerComponent {
+impl<T: Write + Send + 'static> Drop for TimerComponent<T> {
     fn drop(&mut self) {
         let _ = self.erase();
         self.abort_handle();
@@ -0,0 +1,48 @@
+use std::io::{Stdout, Write};
+
+use accessors_rs::Accessors;
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Color, Style, StyleProperty},
+};
+
+use super::Position;
+
+pub const TITLE_WIDTH: u16 = 42;
+pub const TITLE_HEIGHT: u16 = 6;
+const TITLE: [&str; TITLE_HEIGHT as usize] = [
+    "███████╗███╗   ██╗ █████╗ ██╗  ██╗███████╗",
+    "██╔════╝████╗  ██║██╔══██╗██║ ██╔╝██╔════╝",
+    "███████╗██╔██╗ ██║███████║█████╔╝ █████╗  ",
+    "╚════██║██║╚██╗██║██╔══██║██╔═██╗ ██╔══╝  ",
+    "███████║██║ ╚████║██║  ██║██║  ██╗███████╗",
+    "╚══════╝╚═╝  ╚═══╝╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝",
+];
+
+/// Name of the game in big letters, shown above the menus.
+#[derive(Accessors)]
+pub struct TitleComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    style: Style,
+    #[accessors(get_copy)]
+    position: Position,
+}
+
+impl<T: Write + Send> TitleComponent<T> {
+    pub fn new(screen: SharedScreen<T>, position: Position) -> Self {
+        Self {
+            screen,
+            position,
+            style: Style::from([StyleProperty::Color(Color::RGB(184, 195, 52))]),
+        }
+    }
+
+    pub fn render(&self) -> super::Result<()> {
+        let mut screen = self.screen.lock()?;
+        let Position { x, y } = self.position;
+        for (i, y) in (y..y + TITLE_HEIGHT).enumerate() {
+            screen.draw_str(x, y, TITLE[i], &self.style);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use std::{
+    collections::HashSet,
+    io::{Stdout, Write},
+};
+
+use snake_in_terminal::terminus::{
+    screen::SharedScreen,
+    style::{Color, Style, StyleProperty},
+};
+
+use super::{Boundary, Position};
+
+/// Walls of a map, joined together like the lines of the border.
+pub struct WallsComponent<T: Write + Send = Stdout> {
+    screen: SharedScreen<T>,
+    style: Style,
+}
+
+impl<T: Write + Send> WallsComponent<T> {
+    pub fn new(screen: SharedScreen<T>) -> Self {
+        Self {
+            screen,
+            style: Style::from([StyleProperty::Color(Color::RGB(255, 255, 255))]),
+        }
+    }
+
+    /// Draw the walls inside the boundary, the others are hidden by the border.
+    pub fn render(&self, walls: &HashSet<Position>, boundary: Boundary) -> super::Result<()> {
+        let mut screen = self.screen.lock()?;
+        for wall in walls.iter().filter(|wall| boundary.is_inside(**wall)) {
+            screen.draw_char(wall.x, wall.y, glyph(walls, *wall), &self.style);
+        }
+        Ok(())
+    }
+}
+
+/// Line glyph joining the wall to the walls next to it.
+fn glyph(walls: &HashSet<Position>, Position { x, y }: Position) -> char {
+    let is_wall = |x: u16, y: u16| walls.contains(&Position::new(x, y));
+    let up = is_wall(x, y.wrapping_sub(1));
+    let down = is_wall(x, y.wrapping_add(1));
+    let left = is_wall(x.wrapping_sub(1), y);
+    let right = is_wall(x.wrapping_add(1), y);
+    match (up, down, left, right) {
+        (false, false, false, false) => '■',
+        (false, false, _, _) => '─',
+        (_, _, false, false) => '│',
+        (false, true, false, true) => '╭',
+        (false, true, true, false) => '╮',
+        (true, false, false, true) => '╰',
+        (true, false, true, false) => '╯',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (true, true, true, true) => '┼',
+    }
+}
@@ -0,0 +1,318 @@
+pub mod hamiltonian;
+
+use std::collections::{HashSet, VecDeque};
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    component::Position,
+    controller::{BoardView, Controller},
+    game_state::snake::Direction,
+};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+/// How well the computer plays, the default is a fair opponent.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Skill {
+    /// Steps before the computer notices where a new apple is.
+    pub reaction_delay: u64,
+    /// Chance, in percent, that the computer turns at random instead of following its path.
+    pub mistake_rate: u8,
+}
+
+impl Default for Skill {
+    fn default() -> Self {
+        Self {
+            reaction_delay: 3,
+            mistake_rate: 2,
+        }
+    }
+}
+
+/// Snake moved by the computer.
+///
+/// It follows the shortest path to the apple around the walls and the snakes, found with
+/// a breadth-first search over the cells of the board. Without a path, it goes where it has
+/// the most room.
+pub struct ComputerPlayer {
+    skill: Skill,
+    /// Last apple seen on the board and the tick it was seen at.
+    seen_apple: Option<(Position, u64)>,
+    /// Apple the computer heads to, it is the seen apple once the reaction delay is over.
+    target: Option<Position>,
+    rng: ChaCha8Rng,
+}
+
+impl ComputerPlayer {
+    /// Computer with its mistakes drawn from the seed.
+    pub fn new(skill: Skill, seed: u64) -> Self {
+        Self {
+            skill,
+            seen_apple: None,
+            target: None,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Follow a new apple once the reaction delay is over.
+    fn look_at_apple(&mut self, board: &BoardView) {
+        let apple = board.apple();
+        if apple != self.seen_apple.map(|(position, _)| position) {
+            self.seen_apple = apple.map(|position| (position, board.tick()));
+        }
+        if let Some((position, seen_at)) = self.seen_apple {
+            if board.tick() >= seen_at + self.skill.reaction_delay {
+                self.target = Some(position);
+            }
+        }
+        // Nothing left to reach once the snake is on the target.
+        if self.target == Some(board.snake().head().position()) {
+            self.target = None;
+        }
+    }
+}
+
+impl Controller for ComputerPlayer {
+    fn next_direction(&mut self, board: &BoardView) -> Option<Direction> {
+        self.look_at_apple(board);
+        let snake = board.snake();
+        let direction = snake.head().direction();
+        let candidates: Vec<Direction> = DIRECTIONS
+            .into_iter()
+            .filter(|candidate| *candidate != direction.opposite())
+            .collect();
+
+        let next_direction = if self.rng.gen_range(0..100) < self.skill.mistake_rate {
+            candidates.choose(&mut self.rng).copied()
+        } else {
+            let obstacles = obstacles(board);
+            self.target
+                .and_then(|target| path_to(board, &obstacles, target))
+                .or_else(|| roomiest(board, &obstacles, &candidates))
+        };
+        next_direction.filter(|next_direction| *next_direction != direction)
+    }
+}
+
+/// Cells a snake cannot go through.
+fn obstacles(board: &BoardView) -> HashSet<Position> {
+    board
+        .snakes()
+        .flat_map(|snake| snake.nodes())
+        .map(|node| node.position())
+        .chain(board.walls().iter().copied())
+        .collect()
+}
+
+/// Free cell next to the position, the snake wraps around the board in the wrap-around mode.
+fn neighbour(
+    board: &BoardView,
+    obstacles: &HashSet<Position>,
+    position: Position,
+    direction: Direction,
+) -> Option<Position> {
+    let next = board.next_position(position, direction);
+    (board.boundary().is_inside(next) && !obstacles.contains(&next)).then_some(next)
+}
+
+/// First direction of the shortest path from the head of the snake to the target.
+fn path_to(
+    board: &BoardView,
+    obstacles: &HashSet<Position>,
+    target: Position,
+) -> Option<Direction> {
+    let head = board.snake().head();
+    let mut visited = HashSet::from([head.position()]);
+    let mut queue: VecDeque<(Position, Direction)> = DIRECTIONS
+        .into_iter()
+        .filter(|direction| *direction != head.direction().opposite())
+        .filter_map(|direction| {
+            neighbour(board, obstacles, head.position(), direction)
+                .map(|position| (position, direction))
+        })
+        .collect();
+    while let Some((position, first_direction)) = queue.pop_front() {
+        if position == target {
+            return Some(first_direction);
+        }
+        if !visited.insert(position) {
+            continue;
+        }
+        for direction in DIRECTIONS {
+            if let Some(next) = neighbour(board, obstacles, position, direction) {
+                if !visited.contains(&next) {
+                    queue.push_back((next, first_direction));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Direction leading to the most free cells the snake can reach.
+fn roomiest(
+    board: &BoardView,
+    obstacles: &HashSet<Position>,
+    candidates: &[Direction],
+) -> Option<Direction> {
+    let head = board.snake().head().position();
+    candidates
+        .iter()
+        .filter_map(|direction| {
+            neighbour(board, obstacles, head, *direction)
+                .map(|next| (*direction, room(board, obstacles, next)))
+        })
+        .max_by_key(|(_, room)| *room)
+        .map(|(direction, _)| direction)
+}
+
+/// Number of free cells reachable from the position.
+fn room(board: &BoardView, obstacles: &HashSet<Position>, start: Position) -> usize {
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(position) = queue.pop_front() {
+        for direction in DIRECTIONS {
+            if let Some(next) = neighbour(board, obstacles, position, direction) {
+                if visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    visited.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{obstacles, path_to, ComputerPlayer, Skill};
+    use crate::{
+        component::{Boundary, Dimension, Position},
+        controller::{self, BoardView, Controller},
+        game_state::{fruit::FruitRates, snake::Direction, GameEvent, GameState, Player},
+        map::Map,
+        settings::{GameMode, Opponent},
+    };
+
+    /// Solo round, the snake head is on (10, 0) going right.
+    fn state(seed: u64, map: Option<Map>) -> GameState {
+        let board = Boundary::new(Position::new(0, 0), Dimension::new(30, 12));
+        let map = map.map(Arc::new);
+        GameState::new(
+            board,
+            seed,
+            GameMode::Classic,
+            Opponent::Solo,
+            map,
+            FruitRates::NONE,
+        )
+    }
+
+    /// Follow the shortest path to the target, the snake must never die on the way.
+    fn follow_path(state: &mut GameState, target: Position) -> usize {
+        let mut steps = 0;
+        while state.snake().head().position() != target {
+            let board = BoardView::new(state, Player::One);
+            let direction = path_to(&board, &obstacles(&board), target).expect("A path exists.");
+            let events = state.step(&[(Player::One, direction)]);
+            assert!(!events.contains(&GameEvent::GameOver));
+            steps += 1;
+        }
+        steps
+    }
+
+    #[test]
+    fn reach_the_apple_by_the_shortest_path() {
+        for seed in 0..20 {
+            let mut state = state(seed, None);
+            let apple = state.apple().unwrap();
+            let head = state.snake().head().position();
+            let distance = head.x.abs_diff(apple.x) + head.y.abs_diff(apple.y);
+
+            let steps = follow_path(&mut state, apple);
+
+            // The snake cannot turn back, it goes around its body to reach an apple behind it.
+            let detour = if apple.x <= head.x && apple.y == 0 {
+                2
+            } else {
+                0
+            };
+            assert!(steps <= (distance + detour) as usize, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn go_around_the_body() {
+        let mut state = state(0, None);
+        state.step(&[(Player::One, Direction::Down)]);
+        for _ in 0..3 {
+            state.step(&[(Player::One, Direction::Left)]);
+        }
+        // The head is on (7, 1) going left, under the body.
+        assert_eq!(state.snake().head().position(), Position::new(7, 1));
+
+        let target = Position::new(11, 0);
+        let board = BoardView::new(&state, Player::One);
+        assert_eq!(
+            path_to(&board, &obstacles(&board), target),
+            Some(Direction::Down)
+        );
+        follow_path(&mut state, target);
+    }
+
+    #[test]
+    fn go_around_the_walls() {
+        // A wall down the board but its last row, the target is behind it.
+        let mut rows: Vec<String> = (0..12)
+            .map(|_| format!("{}#{}", ".".repeat(19), ".".repeat(10)))
+            .collect();
+        rows[0].replace_range(0..1, ">");
+        rows[11] = ".".repeat(30);
+        let map = Map::from_rows("wall", rows.iter().map(String::as_str).enumerate()).unwrap();
+        let mut state = state(0, Some(map));
+        let target = Position::new(29, 0);
+
+        let steps = follow_path(&mut state, target);
+        assert_eq!(steps, 41);
+    }
+
+    #[test]
+    fn find_no_path_to_an_unreachable_cell() {
+        let state = state(0, None);
+        let board = BoardView::new(&state, Player::One);
+        assert_eq!(
+            path_to(&board, &obstacles(&board), Position::new(5, 0)),
+            None
+        );
+        assert_eq!(
+            path_to(&board, &obstacles(&board), Position::new(30, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn eat_the_apples_without_mistakes() {
+        let skill = Skill {
+            reaction_delay: 0,
+            mistake_rate: 0,
+        };
+        for seed in 0..5 {
+            let mut state = state(seed, None);
+            let mut controllers: Vec<(Player, Box<dyn Controller>)> =
+                vec![(Player::One, Box::new(ComputerPlayer::new(skill, seed)))];
+            while state.score() < 1000 {
+                let turns = controller::turns(&mut controllers, &state);
+                let events = state.step(&turns);
+                assert!(!events.contains(&GameEvent::GameOver), "seed {seed}");
+            }
+        }
+    }
+}
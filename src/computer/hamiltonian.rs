@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use crate::{
+    component::{Boundary, Dimension, Position},
+    controller::{BoardView, Controller},
+    game_state::snake::Direction,
+};
+
+use super::DIRECTIONS;
+
+/// Cells kept free between the head and the tail when taking a shortcut, so the snake
+/// still has room once it grows.
+const SHORTCUT_MARGIN: usize = 4;
+
+/// Snake following a cycle going once through every cell of the board, so it never dies
+/// and ends up filling the board.
+///
+/// While the snake is shorter than half the board, it skips parts of the cycle to reach
+/// the apple sooner, as long as it does not pass its tail.
+pub struct CycleFollower {
+    cycle: Vec<Position>,
+    /// Place of each cell in the cycle.
+    index: HashMap<Position, usize>,
+}
+
+impl CycleFollower {
+    /// Cycle over the board, which must have an even side, see `board_for`.
+    ///
+    /// The cycle starts along the top row toward the right, where a new snake starts.
+    pub fn new(board: Boundary) -> Self {
+        let Dimension { width, height } = board.dimension();
+        let cells = if height % 2 == 0 {
+            cycle(width, height)
+        } else {
+            // The same cycle turned on its side, reversed to start along the top row.
+            let mut cells: Vec<(u16, u16)> = cycle(height, width)
+                .into_iter()
+                .map(|(x, y)| (y, x))
+                .collect();
+            cells.reverse();
+            cells.rotate_right(1);
+            cells
+        };
+        let cycle: Vec<Position> = cells
+            .into_iter()
+            .map(|(x, y)| board.position() + Position::new(x, y))
+            .collect();
+        let index = cycle
+            .iter()
+            .enumerate()
+            .map(|(i, position)| (*position, i))
+            .collect();
+        Self { cycle, index }
+    }
+
+    /// Part of the boundary with a cycle over it, a column is left out when both sides are odd.
+    pub fn board_for(boundary: Boundary) -> Boundary {
+        let Dimension { width, height } = boundary.dimension();
+        let width = if width % 2 == 1 && height % 2 == 1 {
+            width - 1
+        } else {
+            width
+        };
+        Boundary::new(boundary.position(), Dimension::new(width, height))
+    }
+}
+
+impl Controller for CycleFollower {
+    fn next_direction(&mut self, board: &BoardView) -> Option<Direction> {
+        let snake = board.snake();
+        let head = snake.head();
+        let head_index = *self.index.get(&head.position())?;
+        let tail_index = *self.index.get(&snake.tail().position())?;
+        let length = self.cycle.len();
+        let distance = |from: usize, to: usize| (to + length - from) % length;
+
+        let mut best = self.cycle[(head_index + 1) % length];
+        let apple = board.apple().and_then(|apple| self.index.get(&apple));
+        if let Some(apple) = apple.filter(|_| snake.nodes().len() < length / 2) {
+            let room = distance(head_index, tail_index);
+            for direction in DIRECTIONS {
+                let next = snake.get_position_toward(head.position(), direction);
+                let Some(next_index) = self.index.get(&next).copied() else {
+                    continue;
+                };
+                let is_safe = distance(head_index, next_index) + SHORTCUT_MARGIN < room
+                    && snake.nodes().iter().all(|node| node.position() != next);
+                if is_safe && distance(next_index, *apple) < distance(self.index[&best], *apple) {
+                    best = next;
+                }
+            }
+        }
+        DIRECTIONS
+            .into_iter()
+            .find(|direction| snake.get_position_toward(head.position(), *direction) == best)
+            .filter(|direction| *direction != head.direction())
+    }
+}
+
+/// Cells of a cycle over a board with an even height, starting at the top left cell.
+///
+/// It goes right along the top row, zigzags down the other columns, and comes back up
+/// the first column.
+fn cycle(width: u16, height: u16) -> Vec<(u16, u16)> {
+    let mut cells: Vec<(u16, u16)> = (0..width).map(|x| (x, 0)).collect();
+    for y in 1..height {
+        if y % 2 == 1 {
+            cells.extend((1..width).rev().map(|x| (x, y)));
+        } else {
+            cells.extend((1..width).map(|x| (x, y)));
+        }
+    }
+    cells.extend((1..height).rev().map(|y| (0, y)));
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{cycle, CycleFollower};
+    use crate::{
+        component::{Boundary, Dimension, Position},
+        controller::{self, Controller},
+        game_state::{fruit::FruitRates, GameState, Player},
+        settings::{GameMode, Opponent},
+    };
+
+    /// Solo round on a board away from the top left of the screen.
+    fn state(seed: u64, width: u16, height: u16) -> GameState {
+        let board = Boundary::new(Position::new(2, 1), Dimension::new(width, height));
+        GameState::new(
+            board,
+            seed,
+            GameMode::Classic,
+            Opponent::Solo,
+            None,
+            FruitRates::NONE,
+        )
+    }
+
+    #[test]
+    fn go_once_through_every_cell() {
+        for (width, height) in [(12, 6), (4, 2), (11, 4)] {
+            let cells = cycle(width, height);
+            let distinct: HashSet<&(u16, u16)> = cells.iter().collect();
+            assert_eq!(cells.len(), (width * height) as usize);
+            assert_eq!(distinct.len(), cells.len());
+            for (from, to) in cells.iter().zip(cells.iter().cycle().skip(1)) {
+                assert_eq!(from.0.abs_diff(to.0) + from.1.abs_diff(to.1), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn fill_the_board_without_dying() {
+        for (width, height) in [(12, 6), (12, 5), (14, 4)] {
+            for seed in 0..3 {
+                let mut state = state(seed, width, height);
+                let follower = CycleFollower::new(state.boundary());
+                let mut controllers: Vec<(Player, Box<dyn Controller>)> =
+                    vec![(Player::One, Box::new(follower))];
+                while !state.is_over() {
+                    let turns = controller::turns(&mut controllers, &state);
+                    state.step(&turns);
+                }
+
+                assert!(state.is_won(), "{width}x{height}, seed {seed}");
+                assert_eq!(state.death_cause(), None);
+                assert_eq!(
+                    state.snake().nodes().len(),
+                    (width * height) as usize,
+                    "{width}x{height}, seed {seed}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn leave_a_column_out_when_both_sides_are_odd() {
+        let board =
+            |width, height| Boundary::new(Position::new(2, 1), Dimension::new(width, height));
+
+        let odd = CycleFollower::board_for(board(13, 5));
+        assert_eq!(odd.position(), Position::new(2, 1));
+        assert_eq!(odd.dimension(), Dimension::new(12, 5));
+        for (width, height) in [(12, 5), (13, 6), (12, 6)] {
+            assert_eq!(
+                CycleFollower::board_for(board(width, height)).dimension(),
+                Dimension::new(width, height)
+            );
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::{collections::HashSet, sync::Arc};
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::{
+    component::{Boundary, Position},
+    game_state::{
+        snake::{Direction, Snake},
+        GameState, Player,
+    },
+    replay::{Replay, ReplayEntry},
+};
+
+/// Something that moves a snake: a player at the keyboard, a replay or a bot.
+///
+/// The game asks every controller for a direction before each step. A new bot only
+/// has to implement this trait and be registered by name in `BotRegistry::new`.
+pub trait Controller: Send {
+    /// Direction to take at the next step, none to keep going straight.
+    ///
+    /// A direction going back into the snake is ignored by the game.
+    fn next_direction(&mut self, board: &BoardView) -> Option<Direction>;
+}
+
+/// Board as seen by the controller of a player, it cannot change the game.
+pub struct BoardView<'a> {
+    state: &'a GameState,
+    player: Player,
+}
+
+impl<'a> BoardView<'a> {
+    pub fn new(state: &'a GameState, player: Player) -> Self {
+        Self { state, player }
+    }
+
+    /// Player whose snake is moved by the controller.
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.state.boundary()
+    }
+
+    /// Number of steps since the start of the round.
+    pub fn tick(&self) -> u64 {
+        self.state.tick()
+    }
+
+    pub fn apple(&self) -> Option<Position> {
+        self.state.apple()
+    }
+
+    pub fn walls(&self) -> &'a HashSet<Position> {
+        self.state.walls()
+    }
+
+    /// Snake moved by the controller, its nodes go from the head to the tail.
+    pub fn snake(&self) -> &'a Snake {
+        self.state.snake_of(self.player)
+    }
+
+    /// Every snake on the board.
+    pub fn snakes(&self) -> impl Iterator<Item = &'a Snake> {
+        self.state.snakes()
+    }
+
+    /// Cell next to the position, across the edge of the board in the wrap-around mode.
+    pub fn next_position(&self, position: Position, direction: Direction) -> Position {
+        self.snake().get_position_toward(position, direction)
+    }
+}
+
+/// Ask every controller where its snake goes, for the next step of the game.
+pub fn turns(
+    controllers: &mut [(Player, Box<dyn Controller>)],
+    state: &GameState,
+) -> Vec<(Player, Direction)> {
+    controllers
+        .iter_mut()
+        .filter_map(|(player, controller)| {
+            let direction = controller.next_direction(&BoardView::new(state, *player));
+            direction.map(|direction| (*player, direction))
+        })
+        .collect()
+}
+
+/// Directions typed by a player, queued until the next step.
+pub struct KeyboardController {
+    directions: UnboundedReceiver<Direction>,
+}
+
+impl KeyboardController {
+    /// Controller and the sender of the directions typed by the player.
+    pub fn new() -> (Self, UnboundedSender<Direction>) {
+        let (sender, directions) = mpsc::unbounded_channel();
+        (Self { directions }, sender)
+    }
+}
+
+impl Controller for KeyboardController {
+    /// The last direction typed since the previous step which the snake can take.
+    fn next_direction(&mut self, board: &BoardView) -> Option<Direction> {
+        let back = board.snake().head().direction().opposite();
+        let mut next_direction = None;
+        while let Ok(direction) = self.directions.try_recv() {
+            if direction != back {
+                next_direction = Some(direction);
+            }
+        }
+        next_direction
+    }
+}
+
+/// Turns of a player recorded in a replay.
+pub struct ReplayController {
+    replay: Arc<Replay>,
+    /// Index of the next replay entry to play.
+    next_entry: usize,
+}
+
+impl ReplayController {
+    pub fn new(replay: Arc<Replay>) -> Self {
+        Self {
+            replay,
+            next_entry: 0,
+        }
+    }
+}
+
+impl Controller for ReplayController {
+    /// The last turn recorded at this step, the earlier ones had no effect on the game.
+    fn next_direction(&mut self, board: &BoardView) -> Option<Direction> {
+        let mut next_direction = None;
+        let entries = &self.replay.entries()[self.next_entry..];
+        for entry in entries.iter().take_while(|e| e.tick() <= board.tick()) {
+            match *entry {
+                ReplayEntry::Turn(_, player, direction) if player == board.player() => {
+                    next_direction = Some(direction);
+                }
+                _ => {}
+            }
+            self.next_entry += 1;
+        }
+        next_direction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{turns, BoardView, Controller, KeyboardController, ReplayController};
+    use crate::{
+        component::{Boundary, Dimension, Position},
+        game_state::{fruit::FruitRates, snake::Direction, GameState, Player},
+        replay::Replay,
+        settings::{GameMode, Opponent},
+    };
+
+    /// Round against a second player on a board with its top left cell on (2, 3).
+    fn state(mode: GameMode) -> GameState {
+        let board = Boundary::new(Position::new(2, 3), Dimension::new(21, 5));
+        GameState::new(board, 0, mode, Opponent::Player, None, FruitRates::NONE)
+    }
+
+    /// Controller always giving the same answer.
+    struct Fixed(Option<Direction>);
+
+    impl Controller for Fixed {
+        fn next_direction(&mut self, _board: &BoardView) -> Option<Direction> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn show_the_snake_of_the_player() {
+        let state = state(GameMode::Classic);
+        let one = BoardView::new(&state, Player::One);
+        let two = BoardView::new(&state, Player::Two);
+
+        assert_eq!(one.player(), Player::One);
+        assert_eq!(one.snake().head().position(), Position::new(12, 3));
+        assert_eq!(two.snake().head().position(), Position::new(12, 7));
+        assert_eq!(one.snakes().count(), 2);
+        assert_eq!(one.tick(), 0);
+        assert_eq!(one.apple(), state.apple());
+    }
+
+    #[test]
+    fn give_the_next_cell_across_the_edge_when_wrapping() {
+        let classic = state(GameMode::Classic);
+        let wrap = state(GameMode::Wrap);
+        let top_left = Position::new(2, 3);
+
+        let classic = BoardView::new(&classic, Player::One);
+        assert_eq!(
+            classic.next_position(top_left, Direction::Up),
+            Position::new(2, 2)
+        );
+        let wrap = BoardView::new(&wrap, Player::One);
+        assert_eq!(
+            wrap.next_position(top_left, Direction::Up),
+            Position::new(2, 7)
+        );
+        assert_eq!(
+            wrap.next_position(top_left, Direction::Left),
+            Position::new(22, 3)
+        );
+    }
+
+    #[test]
+    fn keep_only_the_controllers_turning() {
+        let state = state(GameMode::Classic);
+        let mut controllers: Vec<(Player, Box<dyn Controller>)> = vec![
+            (Player::One, Box::new(Fixed(None))),
+            (Player::Two, Box::new(Fixed(Some(Direction::Up)))),
+        ];
+
+        assert_eq!(
+            turns(&mut controllers, &state),
+            [(Player::Two, Direction::Up)]
+        );
+    }
+
+    #[test]
+    fn take_the_last_direction_typed_which_does_not_go_back() {
+        let state = state(GameMode::Classic);
+        let (mut keyboard, sender) = KeyboardController::new();
+        for direction in [Direction::Up, Direction::Down, Direction::Left] {
+            sender.send(direction).unwrap();
+        }
+
+        let board = BoardView::new(&state, Player::One);
+        assert_eq!(keyboard.next_direction(&board), Some(Direction::Down));
+        assert_eq!(keyboard.next_direction(&board), None);
+    }
+
+    #[test]
+    fn play_the_turns_of_the_player_recorded_up_to_the_tick() {
+        let mut state = state(GameMode::Classic);
+        let mut replay = Replay::new(
+            0,
+            GameMode::Classic,
+            Opponent::Player,
+            None,
+            FruitRates::NONE,
+            Dimension::new(21, 5),
+        );
+        replay.record_turn(0, Player::Two, Direction::Up);
+        replay.record_turn(1, Player::One, Direction::Up);
+        replay.record_turn(1, Player::One, Direction::Down);
+        let mut controller = ReplayController::new(Arc::new(replay));
+
+        assert_eq!(
+            controller.next_direction(&BoardView::new(&state, Player::One)),
+            None
+        );
+        state.step(&[]);
+        assert_eq!(
+            controller.next_direction(&BoardView::new(&state, Player::One)),
+            Some(Direction::Down)
+        );
+    }
+}
@@ -1,29 +1,58 @@
-use crossterm::event::{self, Event, KeyCode};
-use tokio::sync::mpsc::{error::SendError, UnboundedSender};
+use std::{io, time::Duration};
+
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// How long to wait for an event before checking if the inputs are still needed.
+const POLL_TIMEOUT: Duration = Duration::from_millis(100);
 
 pub enum GameInput {
     Up,
     Down,
     Left,
     Right,
+    Pause,
+    Restart,
+    /// Choose the selected menu item.
+    Select,
+    /// Leave the current screen.
+    Back,
+    /// Any other character typed.
+    Char(char),
+    /// The terminal was resized to a new width and height.
+    Resize(u16, u16),
+    /// Leave the program from any screen.
     Quit,
 }
 
-pub fn read_inputs(tx: UnboundedSender<GameInput>) -> Result<(), SendError<GameInput>> {
-    while let Ok(event) = event::read() {
-        match event {
+/// Send the inputs until the receiver is dropped.
+pub fn read_inputs(tx: UnboundedSender<GameInput>) -> io::Result<()> {
+    while !tx.is_closed() {
+        if !event::poll(POLL_TIMEOUT)? {
+            continue;
+        }
+        let input = match event::read()? {
             Event::Key(event) => match event.code {
-                KeyCode::Up => tx.send(GameInput::Up)?,
-                KeyCode::Down => tx.send(GameInput::Down)?,
-                KeyCode::Left => tx.send(GameInput::Left)?,
-                KeyCode::Right => tx.send(GameInput::Right)?,
-                KeyCode::Char('q') => {
-                    tx.send(GameInput::Quit)?;
-                    break;
+                KeyCode::Up => GameInput::Up,
+                KeyCode::Down => GameInput::Down,
+                KeyCode::Left => GameInput::Left,
+                KeyCode::Right => GameInput::Right,
+                KeyCode::Char('p') | KeyCode::Char(' ') => GameInput::Pause,
+                KeyCode::Char('r') => GameInput::Restart,
+                KeyCode::Enter => GameInput::Select,
+                // Raw mode turn Ctrl-C into a key event instead of SIGINT.
+                KeyCode::Char('c') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    GameInput::Quit
                 }
-                _ => (),
+                KeyCode::Char('q') | KeyCode::Esc => GameInput::Back,
+                KeyCode::Char(c) => GameInput::Char(c),
+                _ => continue,
             },
-            _ => (),
+            Event::Resize(width, height) => GameInput::Resize(width, height),
+            _ => continue,
+        };
+        if tx.send(input).is_err() {
+            break;
         }
     }
     Ok(())
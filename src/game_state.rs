@@ -0,0 +1,743 @@
+pub mod fruit;
+pub mod snake;
+pub mod spawn;
+
+use std::{cmp::Ordering, collections::HashSet, sync::Arc, time::Duration};
+
+use accessors_rs::Accessors;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    component::{Boundary, Dimension, Position},
+    map::Map,
+    settings::{GameMode, Opponent},
+};
+
+use self::{
+    fruit::{
+        Fruit, FruitKind, FruitRates, GOLDEN_SCORE, POISON_PENALTY, SHRINK_NODES,
+        SLOW_MOTION_FACTOR, SLOW_MOTION_TICKS,
+    },
+    snake::{Direction, Snake, SnakeNode, SNAKE_START_LENGTH},
+    spawn::SpawnStrategy,
+};
+
+/// Points given for each apple eaten.
+pub const APPLE_SCORE: u32 = 100;
+
+/// Owner of a snake, the second player only play against an opponent.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+/// What killed a snake.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DeathCause {
+    /// The snake went out of the board or on a wall of the map.
+    Wall,
+    /// The snake bit its own body.
+    Body,
+    /// The snake hit the other snake.
+    Snake,
+}
+
+impl DeathCause {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeathCause::Wall => "wall",
+            DeathCause::Body => "body",
+            DeathCause::Snake => "snake",
+        }
+    }
+}
+
+/// What happened during a step of the game.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GameEvent {
+    DirectionChanged(Player, Direction),
+    AppleEaten(Player, Position),
+    AppleSpawned(Position),
+    FruitEaten(Player, FruitKind, Position),
+    FruitSpawned(FruitKind, Position),
+    /// The fruit at the position expired before a snake ate it.
+    FruitVanished(Position),
+    /// The snake moved, the position is the one of its new head.
+    Moved(Player, Position),
+    /// The snakes fill every free cell of the board, so no apple can spawn.
+    Won,
+    GameOver,
+}
+
+/// Rules of the game without any input or output.
+///
+/// The game only change when `step` is called, so it can run as fast as needed
+/// and render by anything that read it.
+#[derive(Accessors, Clone)]
+#[accessors(get)]
+pub struct GameState {
+    #[accessors(get_copy)]
+    boundary: Boundary,
+    #[accessors(get_copy)]
+    mode: GameMode,
+    /// Who controls the second snake, there is none in a solo round.
+    #[accessors(get_copy)]
+    opponent: Opponent,
+    /// Level played, the board is empty when not set.
+    map: Option<Arc<Map>>,
+    walls: HashSet<Position>,
+    spawn_strategy: SpawnStrategy,
+    snake: Snake,
+    /// Snake of the second player, only against an opponent.
+    second_snake: Option<Snake>,
+    /// None once the snakes fill the board.
+    #[accessors(get_copy)]
+    apple: Option<Position>,
+    /// Chance that a fruit spawn each time an apple is eaten.
+    #[accessors(get_copy)]
+    fruit_rates: FruitRates,
+    /// Fruit on the board next to the apple, there is at most one.
+    #[accessors(get_copy)]
+    fruit: Option<Fruit>,
+    /// Tick until which the game is slowed down.
+    slow_motion_until: u64,
+    #[accessors(get_copy)]
+    score: u32,
+    #[accessors(get_copy)]
+    second_score: u32,
+    #[accessors(get_copy)]
+    is_over: bool,
+    /// True when the game is over because the snakes fill the board.
+    #[accessors(get_copy)]
+    is_won: bool,
+    /// What killed the snake of the first player, none while it is alive.
+    #[accessors(get_copy)]
+    death_cause: Option<DeathCause>,
+    /// Player still alive, or with the best score, once a round against an opponent is over.
+    ///
+    /// None for a draw and in a solo round.
+    #[accessors(get_copy)]
+    winner: Option<Player>,
+    /// Number of steps the snake moved.
+    #[accessors(get_copy)]
+    tick: u64,
+    /// Seed of the random number generator at the start of the round.
+    #[accessors(get_copy)]
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl GameState {
+    /// Start a round, every random choice of the round come from the seed.
+    ///
+    /// With a map, the board is never bigger than the map.
+    /// The second snake start on the opposite corner of the first one, heading the other way.
+    pub fn new(
+        boundary: Boundary,
+        seed: u64,
+        mode: GameMode,
+        opponent: Opponent,
+        map: Option<Arc<Map>>,
+        fruit_rates: FruitRates,
+    ) -> Self {
+        let boundary = Self::board_of(map.as_deref(), boundary);
+        let (start, area) = match &map {
+            Some(map) => (
+                map.start_on(boundary),
+                Boundary::new(boundary.position(), map.dimension()),
+            ),
+            None => (
+                SnakeNode::new(boundary.position(), Direction::Right),
+                boundary,
+            ),
+        };
+        let wrap_boundary = Self::wrap_boundary(mode, boundary);
+        let mut snake = Snake::new(start.clone(), SNAKE_START_LENGTH);
+        snake.set_wrap_boundary(wrap_boundary);
+        let second_snake = match opponent {
+            Opponent::Solo => None,
+            Opponent::Player | Opponent::Computer => {
+                let mut snake = Snake::new(Self::mirror(&start, area), SNAKE_START_LENGTH);
+                snake.set_wrap_boundary(wrap_boundary);
+                Some(snake)
+            }
+        };
+        let mut state = Self {
+            boundary,
+            mode,
+            opponent,
+            walls: map
+                .as_ref()
+                .map(|map| map.walls_on(boundary))
+                .unwrap_or_default(),
+            spawn_strategy: match &map {
+                Some(map) if !map.apple_spawns().is_empty() => {
+                    SpawnStrategy::SpawnPoints(map.apple_spawns_on(boundary))
+                }
+                _ => SpawnStrategy::Anywhere,
+            },
+            map,
+            snake,
+            second_snake,
+            apple: None,
+            fruit_rates,
+            fruit: None,
+            slow_motion_until: 0,
+            score: 0,
+            second_score: 0,
+            is_over: false,
+            is_won: false,
+            death_cause: None,
+            winner: None,
+            tick: 0,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+        state.apple = state.random_position();
+        state
+    }
+
+    /// Start a new round with a new snake, a new apple and no score.
+    ///
+    /// The seed of the new round is drawn from the current one, so a sequence of rounds
+    /// can be reproduced too.
+    pub fn reset(&mut self) {
+        let seed = self.rng.gen();
+        *self = Self::new(
+            self.boundary,
+            seed,
+            self.mode,
+            self.opponent,
+            self.map.clone(),
+            self.fruit_rates,
+        );
+    }
+
+    /// Apply the direction changes of each player in order, then move the game forward
+    /// by one tick.
+    ///
+    /// A snake dies when its head is on a wall, on its body or on the other snake,
+    /// so both die when their heads meet. Does nothing once the game is over.
+    pub fn step(&mut self, inputs: &[(Player, Direction)]) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.is_over {
+            return events;
+        }
+
+        for (player, direction) in inputs {
+            let changed = self
+                .snake_of_mut(*player)
+                .is_some_and(|snake| snake.change_direction(*direction));
+            if changed {
+                events.push(GameEvent::DirectionChanged(*player, *direction));
+            }
+        }
+
+        let first_death = self.death_cause_of(&self.snake, self.second_snake.as_ref());
+        let is_first_dying = first_death.is_some();
+        let is_second_dying = self
+            .second_snake
+            .as_ref()
+            .is_some_and(|snake| self.death_cause_of(snake, Some(&self.snake)).is_some());
+        if is_first_dying || is_second_dying {
+            self.is_over = true;
+            self.death_cause = first_death;
+            self.winner = match (is_first_dying, is_second_dying) {
+                (false, true) => Some(Player::One),
+                (true, false) if self.second_snake.is_some() => Some(Player::Two),
+                _ => None,
+            };
+            events.push(GameEvent::GameOver);
+            return events;
+        }
+
+        let mut ate = false;
+        for player in self.players() {
+            let head = self.snake_of(player).head().position();
+            if let Some(apple) = self.apple.filter(|apple| *apple == head) {
+                ate = true;
+                if let Some(snake) = self.snake_of_mut(player) {
+                    snake.eat();
+                }
+                *self.score_of_mut(player) += APPLE_SCORE;
+                events.push(GameEvent::AppleEaten(player, apple));
+            }
+            if let Some(fruit) = self.fruit.filter(|fruit| fruit.position() == head) {
+                self.fruit = None;
+                self.eat_fruit(player, fruit.kind());
+                events.push(GameEvent::FruitEaten(
+                    player,
+                    fruit.kind(),
+                    fruit.position(),
+                ));
+            }
+        }
+
+        for player in self.players() {
+            if let Some(snake) = self.snake_of_mut(player) {
+                snake.move_forward();
+            }
+            events.push(GameEvent::Moved(
+                player,
+                self.snake_of(player).head().position(),
+            ));
+        }
+        self.tick += 1;
+        if let Some(fruit) = self.fruit.filter(|fruit| fruit.expires_at() <= self.tick) {
+            self.fruit = None;
+            events.push(GameEvent::FruitVanished(fruit.position()));
+        }
+
+        // The apple spawn once the snakes moved, so it is never under a new head.
+        if ate {
+            self.apple = None;
+            self.apple = self.random_position();
+            // The fruit make room for the apple on a full board.
+            if let (None, Some(fruit)) = (self.apple, self.fruit) {
+                self.fruit = None;
+                events.push(GameEvent::FruitVanished(fruit.position()));
+                self.apple = self.random_position();
+            }
+            match self.apple {
+                Some(apple) => {
+                    events.push(GameEvent::AppleSpawned(apple));
+                    events.extend(self.spawn_fruit());
+                }
+                None => {
+                    self.is_over = true;
+                    self.is_won = true;
+                    if self.second_snake.is_some() {
+                        self.winner = match self.score.cmp(&self.second_score) {
+                            Ordering::Greater => Some(Player::One),
+                            Ordering::Less => Some(Player::Two),
+                            Ordering::Equal => None,
+                        };
+                    }
+                    events.push(GameEvent::Won);
+                    events.push(GameEvent::GameOver);
+                }
+            }
+        }
+        events
+    }
+
+    /// True while a slow-motion fruit slow down the game.
+    pub fn is_slow_motion(&self) -> bool {
+        self.tick < self.slow_motion_until
+    }
+
+    /// Time between two steps for the interval of the level, longer during a slow motion.
+    pub fn tick_interval(&self, interval: Duration) -> Duration {
+        if self.is_slow_motion() {
+            interval * SLOW_MOTION_FACTOR
+        } else {
+            interval
+        }
+    }
+
+    /// Change the boundary, the apple move when it is no longer inside it
+    /// and the fruit vanishes.
+    ///
+    /// The apple is not moved once the game is over.
+    pub fn set_boundary(&mut self, boundary: Boundary) -> Vec<GameEvent> {
+        let boundary = Self::board_of(self.map.as_deref(), boundary);
+        self.boundary = boundary;
+        let wrap_boundary = Self::wrap_boundary(self.mode, boundary);
+        self.snake.set_wrap_boundary(wrap_boundary);
+        if let Some(snake) = &mut self.second_snake {
+            snake.set_wrap_boundary(wrap_boundary);
+        }
+        let mut events = Vec::new();
+        if let Some(fruit) = self
+            .fruit
+            .filter(|fruit| !boundary.is_inside(fruit.position()))
+        {
+            self.fruit = None;
+            events.push(GameEvent::FruitVanished(fruit.position()));
+        }
+        if self.is_over || self.apple.is_some_and(|apple| boundary.is_inside(apple)) {
+            return events;
+        }
+        self.apple = None;
+        self.apple = self.random_position();
+        events.extend(self.apple.map(GameEvent::AppleSpawned));
+        events
+    }
+
+    /// False when a part of a snake is outside the boundary.
+    pub fn can_hold_snake(&self) -> bool {
+        self.snakes()
+            .flat_map(|snake| snake.nodes())
+            .all(|node| self.boundary.is_inside(node.position()))
+    }
+
+    /// The snake of the player, the first player always has one.
+    pub fn snake_of(&self, player: Player) -> &Snake {
+        match (player, &self.second_snake) {
+            (Player::Two, Some(snake)) => snake,
+            _ => &self.snake,
+        }
+    }
+
+    /// Every snake on the board, the one of the first player first.
+    pub fn snakes(&self) -> impl Iterator<Item = &Snake> {
+        std::iter::once(&self.snake).chain(&self.second_snake)
+    }
+
+    /// Players with a snake on the board.
+    pub fn players(&self) -> Vec<Player> {
+        match self.second_snake {
+            Some(_) => vec![Player::One, Player::Two],
+            None => vec![Player::One],
+        }
+    }
+
+    fn snake_of_mut(&mut self, player: Player) -> Option<&mut Snake> {
+        match player {
+            Player::One => Some(&mut self.snake),
+            Player::Two => self.second_snake.as_mut(),
+        }
+    }
+
+    fn score_of_mut(&mut self, player: Player) -> &mut u32 {
+        match player {
+            Player::One => &mut self.score,
+            Player::Two => &mut self.second_score,
+        }
+    }
+
+    /// What kills the snake at this step, none when it survives.
+    ///
+    /// The next head is checked against where the other snake will be once both moved,
+    /// so two heads meeting on a cell or crossing each other kill at the same step.
+    fn death_cause_of(&self, snake: &Snake, other: Option<&Snake>) -> Option<DeathCause> {
+        // The next position is always inside the boundary when the snake wrap around it.
+        let next_position = snake.get_next_position();
+        if snake.is_biting_itself() {
+            Some(DeathCause::Body)
+        } else if !self.boundary.is_inside(next_position) || self.walls.contains(&next_position) {
+            Some(DeathCause::Wall)
+        } else if other.is_some_and(|other| self.is_on_moved_snake(next_position, other)) {
+            Some(DeathCause::Snake)
+        } else {
+            None
+        }
+    }
+
+    /// True when the position is on the snake after it moved at this step.
+    ///
+    /// The tail leave its cell, unless the snake eat the apple and grow.
+    fn is_on_moved_snake(&self, position: Position, snake: &Snake) -> bool {
+        let is_growing = self.apple == Some(snake.head().position());
+        let kept_nodes = snake.nodes().len() - usize::from(!is_growing);
+        position == snake.get_next_position()
+            || snake
+                .nodes()
+                .iter()
+                .take(kept_nodes)
+                .any(|node| node.position() == position)
+    }
+
+    /// The node turned around the center of the area, heading the other way.
+    fn mirror(node: &SnakeNode, area: Boundary) -> SnakeNode {
+        let Position { x, y } = node.position();
+        SnakeNode::new(
+            Position::new(
+                area.left() + area.right() - x,
+                area.top() + area.bottom() - y,
+            ),
+            node.direction().opposite(),
+        )
+    }
+
+    /// Part of the boundary used by the map.
+    fn board_of(map: Option<&Map>, boundary: Boundary) -> Boundary {
+        match map {
+            Some(map) => Boundary::new(
+                boundary.position(),
+                Dimension::new(
+                    boundary.dimension().width.min(map.dimension().width),
+                    boundary.dimension().height.min(map.dimension().height),
+                ),
+            ),
+            None => boundary,
+        }
+    }
+
+    fn wrap_boundary(mode: GameMode, boundary: Boundary) -> Option<Boundary> {
+        match mode {
+            GameMode::Classic => None,
+            GameMode::Wrap => Some(boundary),
+        }
+    }
+
+    fn eat_fruit(&mut self, player: Player, kind: FruitKind) {
+        match kind {
+            FruitKind::Golden => *self.score_of_mut(player) += GOLDEN_SCORE,
+            FruitKind::Shrink => {
+                if let Some(snake) = self.snake_of_mut(player) {
+                    snake.shrink(SHRINK_NODES);
+                }
+            }
+            FruitKind::SlowMotion => self.slow_motion_until = self.tick + SLOW_MOTION_TICKS,
+            FruitKind::Poison => {
+                let score = self.score_of_mut(player);
+                *score = score.saturating_sub(POISON_PENALTY);
+            }
+        }
+    }
+
+    /// Maybe spawn a fruit following the rates, when there is none on the board.
+    ///
+    /// Nothing random is drawn without rates, so rounds without fruits stay the same.
+    fn spawn_fruit(&mut self) -> Option<GameEvent> {
+        if self.fruit.is_some() || self.fruit_rates.is_none() {
+            return None;
+        }
+        let kind = self.fruit_rates.choose(&mut self.rng)?;
+        let position = self.random_position()?;
+        self.fruit = Some(Fruit::new(kind, position, self.tick));
+        Some(GameEvent::FruitSpawned(kind, position))
+    }
+
+    /// Random free cell, none when the snakes, the walls, the apple and the fruit fill the board.
+    fn random_position(&mut self) -> Option<Position> {
+        let snake = &self.snake;
+        let second_snake = &self.second_snake;
+        let walls = &self.walls;
+        let apple = self.apple;
+        let fruit = self.fruit.map(|fruit| fruit.position());
+        self.spawn_strategy.spawn(
+            self.boundary,
+            |position| {
+                !walls.contains(&position)
+                    && apple != Some(position)
+                    && fruit != Some(position)
+                    && std::iter::once(snake)
+                        .chain(second_snake)
+                        .flat_map(|snake| snake.nodes())
+                        .all(|node| node.position() != position)
+            },
+            &mut self.rng,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeathCause, GameEvent, GameState, Player, APPLE_SCORE};
+    use crate::{
+        component::{Boundary, Dimension, Position},
+        game_state::{fruit::FruitRates, snake::Direction},
+        settings::{GameMode, Opponent},
+    };
+
+    /// Solo round on a board at the top left of the screen, the snake head is on (10, 0)
+    /// going right and its body goes to (0, 0).
+    fn state(width: u16, height: u16, mode: GameMode) -> GameState {
+        let board = Boundary::new(Position::new(0, 0), Dimension::new(width, height));
+        GameState::new(board, 0, mode, Opponent::Solo, None, FruitRates::NONE)
+    }
+
+    fn head(state: &GameState) -> Position {
+        state.snake().head().position()
+    }
+
+    #[test]
+    fn move_the_snake_forward() {
+        let mut state = state(20, 5, GameMode::Classic);
+        state.apple = Some(Position::new(0, 4));
+
+        let events = state.step(&[]);
+
+        assert_eq!(
+            events,
+            [GameEvent::Moved(Player::One, Position::new(11, 0))]
+        );
+        assert_eq!(state.tick(), 1);
+        assert_eq!(state.snake().nodes().len(), 11);
+        assert!(!state.is_over());
+    }
+
+    #[test]
+    fn die_on_the_edge_of_the_board() {
+        let mut state = state(20, 5, GameMode::Classic);
+        state.apple = Some(Position::new(0, 4));
+
+        let events = state.step(&[(Player::One, Direction::Up)]);
+
+        assert_eq!(
+            events,
+            [
+                GameEvent::DirectionChanged(Player::One, Direction::Up),
+                GameEvent::GameOver
+            ]
+        );
+        assert!(state.is_over());
+        assert!(!state.is_won());
+        assert_eq!(state.death_cause(), Some(DeathCause::Wall));
+        assert_eq!(head(&state), Position::new(10, 0));
+        assert_eq!(state.tick(), 0);
+    }
+
+    #[test]
+    fn die_on_a_wall_of_the_map() {
+        let mut state = state(20, 5, GameMode::Classic);
+        state.apple = Some(Position::new(0, 4));
+        state.walls.insert(Position::new(12, 0));
+
+        assert!(!state.step(&[]).contains(&GameEvent::GameOver));
+        assert_eq!(state.step(&[]), [GameEvent::GameOver]);
+        assert_eq!(state.death_cause(), Some(DeathCause::Wall));
+    }
+
+    #[test]
+    fn die_biting_its_body() {
+        let mut state = state(20, 5, GameMode::Classic);
+        state.apple = Some(Position::new(0, 4));
+
+        for direction in [Direction::Down, Direction::Left, Direction::Up] {
+            state.step(&[(Player::One, direction)]);
+        }
+        assert_eq!(head(&state), Position::new(9, 0));
+        assert!(!state.is_over());
+
+        assert_eq!(state.step(&[]), [GameEvent::GameOver]);
+        assert_eq!(state.death_cause(), Some(DeathCause::Body));
+    }
+
+    #[test]
+    fn ignore_a_turn_back_into_the_body() {
+        let mut state = state(20, 5, GameMode::Classic);
+        state.apple = Some(Position::new(0, 4));
+
+        let events = state.step(&[(Player::One, Direction::Left)]);
+
+        assert_eq!(
+            events,
+            [GameEvent::Moved(Player::One, Position::new(11, 0))]
+        );
+    }
+
+    #[test]
+    fn nothing_happen_once_the_game_is_over() {
+        let mut state = state(20, 5, GameMode::Classic);
+        state.step(&[(Player::One, Direction::Up)]);
+
+        assert!(state.step(&[(Player::One, Direction::Down)]).is_empty());
+        assert_eq!(head(&state), Position::new(10, 0));
+        assert_eq!(state.tick(), 0);
+    }
+
+    #[test]
+    fn go_through_the_edges_in_wrap_mode() {
+        let mut state = state(20, 5, GameMode::Wrap);
+        state.apple = Some(Position::new(0, 2));
+
+        let events = state.step(&[(Player::One, Direction::Up)]);
+
+        assert!(!state.is_over());
+        assert_eq!(head(&state), Position::new(10, 4));
+        assert!(events.contains(&GameEvent::Moved(Player::One, Position::new(10, 4))));
+    }
+
+    #[test]
+    fn eat_the_apple_and_grow() {
+        let mut state = state(20, 5, GameMode::Classic);
+        let apple = Position::new(11, 0);
+        state.apple = Some(apple);
+
+        state.step(&[]);
+        let events = state.step(&[]);
+
+        assert_eq!(events[0], GameEvent::AppleEaten(Player::One, apple));
+        assert_eq!(
+            events[1],
+            GameEvent::Moved(Player::One, Position::new(12, 0))
+        );
+        let new_apple = state.apple().expect("The board has free cells.");
+        assert_eq!(events[2], GameEvent::AppleSpawned(new_apple));
+        assert_ne!(new_apple, apple);
+        assert!(state
+            .snake()
+            .nodes()
+            .iter()
+            .all(|node| node.position() != new_apple));
+        assert_eq!(state.score(), APPLE_SCORE);
+        assert_eq!(state.snake().nodes().len(), 12);
+    }
+
+    #[test]
+    fn win_when_the_snake_fill_the_board() {
+        // The only free cell is at the end of the single row, the snake wrap around to its tail.
+        let mut state = state(12, 1, GameMode::Wrap);
+        assert_eq!(state.apple(), Some(Position::new(11, 0)));
+
+        state.step(&[]);
+        let events = state.step(&[]);
+
+        assert_eq!(
+            events,
+            [
+                GameEvent::AppleEaten(Player::One, Position::new(11, 0)),
+                GameEvent::Moved(Player::One, Position::new(0, 0)),
+                GameEvent::Won,
+                GameEvent::GameOver
+            ]
+        );
+        assert!(state.is_over());
+        assert!(state.is_won());
+        assert_eq!(state.death_cause(), None);
+        assert_eq!(state.apple(), None);
+        assert_eq!(state.snake().nodes().len(), 12);
+    }
+
+    /// Round against a second player, mirrored on the other side of the board.
+    fn versus_state(width: u16, height: u16) -> GameState {
+        let board = Boundary::new(Position::new(0, 0), Dimension::new(width, height));
+        let mut state = GameState::new(
+            board,
+            0,
+            GameMode::Classic,
+            Opponent::Player,
+            None,
+            FruitRates::NONE,
+        );
+        state.apple = Some(Position::new(0, 1));
+        state
+    }
+
+    #[test]
+    fn kill_both_snakes_when_their_heads_meet() {
+        let mut state = versus_state(21, 3);
+        assert_eq!(
+            state
+                .second_snake()
+                .as_ref()
+                .map(|snake| snake.head().position()),
+            Some(Position::new(10, 2))
+        );
+
+        let events = state.step(&[(Player::One, Direction::Down), (Player::Two, Direction::Up)]);
+
+        assert_eq!(events.last(), Some(&GameEvent::GameOver));
+        assert_eq!(head(&state), Position::new(10, 0));
+        assert_eq!(state.death_cause(), Some(DeathCause::Snake));
+        assert_eq!(state.winner(), None);
+    }
+
+    #[test]
+    fn die_before_moving_into_the_other_snake() {
+        let mut state = versus_state(21, 3);
+
+        state.step(&[(Player::One, Direction::Down)]);
+        let events = state.step(&[]);
+
+        assert_eq!(events, [GameEvent::GameOver]);
+        assert_eq!(head(&state), Position::new(10, 1));
+        assert_eq!(state.death_cause(), Some(DeathCause::Snake));
+        assert_eq!(state.winner(), Some(Player::Two));
+    }
+}
@@ -0,0 +1,238 @@
+use accessors_rs::Accessors;
+use rand::Rng;
+
+use crate::component::Position;
+
+/// Points given for a golden fruit.
+pub const GOLDEN_SCORE: u32 = 500;
+/// Points lost for a poison.
+pub const POISON_PENALTY: u32 = 200;
+/// Nodes removed from the tail by a shrinking fruit.
+pub const SHRINK_NODES: usize = 3;
+/// Steps slowed down by a slow-motion fruit.
+pub const SLOW_MOTION_TICKS: u64 = 40;
+/// How much longer the time between two steps is during a slow motion.
+pub const SLOW_MOTION_FACTOR: u32 = 2;
+
+/// Fruit that can spawn next to the apple, each has its own effect.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FruitKind {
+    /// Worth more than an apple, but it does not stay long.
+    Golden,
+    /// Remove nodes from the tail of the snake.
+    Shrink,
+    /// Slow down the game for a few steps.
+    SlowMotion,
+    /// Cost points.
+    Poison,
+}
+
+impl FruitKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FruitKind::Golden => "golden",
+            FruitKind::Shrink => "shrink",
+            FruitKind::SlowMotion => "slow_motion",
+            FruitKind::Poison => "poison",
+        }
+    }
+
+    /// Steps the fruit stay on the board before it vanishes.
+    pub fn lifetime(&self) -> u64 {
+        match self {
+            FruitKind::Golden => 40,
+            FruitKind::Shrink | FruitKind::SlowMotion => 80,
+            FruitKind::Poison => 120,
+        }
+    }
+}
+
+/// Fruit on the board, it vanishes at the tick it expires.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Accessors)]
+#[accessors(get_copy)]
+pub struct Fruit {
+    kind: FruitKind,
+    position: Position,
+    expires_at: u64,
+}
+
+impl Fruit {
+    pub fn new(kind: FruitKind, position: Position, tick: u64) -> Self {
+        Self {
+            kind,
+            position,
+            expires_at: tick + kind.lifetime(),
+        }
+    }
+}
+
+/// Chance, in percent, that each fruit spawn when an apple is eaten.
+///
+/// At most one fruit is on the board, a rate that would make the total exceed 100 is cut.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FruitRates {
+    pub golden: u8,
+    pub shrink: u8,
+    pub slow_motion: u8,
+    pub poison: u8,
+}
+
+impl FruitRates {
+    /// Only apples spawn.
+    pub const NONE: FruitRates = FruitRates {
+        golden: 0,
+        shrink: 0,
+        slow_motion: 0,
+        poison: 0,
+    };
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    /// Random fruit following the rates, none when no fruit spawn this time.
+    pub fn choose(&self, rng: &mut impl Rng) -> Option<FruitKind> {
+        let mut roll = rng.gen_range(0..100);
+        for (kind, rate) in [
+            (FruitKind::Golden, self.golden),
+            (FruitKind::Shrink, self.shrink),
+            (FruitKind::SlowMotion, self.slow_motion),
+            (FruitKind::Poison, self.poison),
+        ] {
+            if roll < rate {
+                return Some(kind);
+            }
+            roll -= rate;
+        }
+        None
+    }
+}
+
+impl Default for FruitRates {
+    fn default() -> Self {
+        Self {
+            golden: 10,
+            shrink: 5,
+            slow_motion: 5,
+            poison: 10,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::{Fruit, FruitKind, FruitRates, SLOW_MOTION_TICKS};
+    use crate::{
+        component::{Boundary, Dimension, Position},
+        game_state::{GameEvent, GameState, Player},
+        settings::{GameMode, Opponent},
+    };
+
+    /// Solo round on a wide board, the snake head is on (10, 0) going right
+    /// and the apple is out of its way.
+    fn state(seed: u64, fruit_rates: FruitRates) -> GameState {
+        let board = Boundary::new(Position::new(0, 0), Dimension::new(200, 5));
+        let mut state = GameState::new(
+            board,
+            seed,
+            GameMode::Classic,
+            Opponent::Solo,
+            None,
+            fruit_rates,
+        );
+        state.apple = Some(Position::new(0, 4));
+        state
+    }
+
+    #[test]
+    fn vanish_once_expired() {
+        let mut state = state(0, FruitRates::NONE);
+        let position = Position::new(5, 3);
+        state.fruit = Some(Fruit::new(FruitKind::Golden, position, 0));
+
+        for _ in 1..FruitKind::Golden.lifetime() {
+            let events = state.step(&[]);
+            assert!(!events.contains(&GameEvent::FruitVanished(position)));
+        }
+        let events = state.step(&[]);
+        assert!(events.contains(&GameEvent::FruitVanished(position)));
+        assert_eq!(state.fruit(), None);
+    }
+
+    #[test]
+    fn slow_down_the_steps_once_eaten() {
+        let mut state = state(0, FruitRates::NONE);
+        let interval = Duration::from_millis(80);
+        state.fruit = Some(Fruit::new(FruitKind::SlowMotion, Position::new(10, 0), 0));
+        assert_eq!(state.tick_interval(interval), interval);
+
+        let events = state.step(&[]);
+        assert!(events.contains(&GameEvent::FruitEaten(
+            Player::One,
+            FruitKind::SlowMotion,
+            Position::new(10, 0)
+        )));
+        for _ in 1..SLOW_MOTION_TICKS {
+            assert!(state.is_slow_motion());
+            assert_eq!(state.tick_interval(interval), interval * 2);
+            state.step(&[]);
+        }
+        assert!(!state.is_slow_motion());
+        assert_eq!(state.tick_interval(interval), interval);
+    }
+
+    #[test]
+    fn spawn_with_the_apple_following_the_rates() {
+        let rates = FruitRates {
+            golden: 0,
+            shrink: 0,
+            slow_motion: 0,
+            poison: 100,
+        };
+        for seed in 0..20 {
+            let mut state = state(seed, rates);
+            state.apple = Some(Position::new(10, 0));
+
+            let events = state.step(&[]);
+            let fruit = state.fruit().unwrap();
+            assert_eq!(fruit.kind(), FruitKind::Poison);
+            assert_eq!(fruit.expires_at(), 1 + FruitKind::Poison.lifetime());
+            assert_ne!(Some(fruit.position()), state.apple());
+            assert!(state
+                .snake()
+                .nodes()
+                .iter()
+                .all(|node| node.position() != fruit.position()));
+            assert!(events.contains(&GameEvent::FruitSpawned(
+                FruitKind::Poison,
+                fruit.position()
+            )));
+        }
+    }
+
+    #[test]
+    fn choose_nothing_past_the_rates() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let rates = FruitRates {
+            golden: 30,
+            shrink: 0,
+            slow_motion: 0,
+            poison: 0,
+        };
+        let chosen: Vec<Option<FruitKind>> = (0..1000).map(|_| rates.choose(&mut rng)).collect();
+        let golden = chosen
+            .iter()
+            .filter(|kind| **kind == Some(FruitKind::Golden))
+            .count();
+        assert!((200..400).contains(&golden));
+        assert!(chosen
+            .iter()
+            .all(|kind| matches!(kind, Some(FruitKind::Golden) | None)));
+        assert!((0..100).all(|_| FruitRates::NONE.choose(&mut rng).is_none()));
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::{HashSet, LinkedList};
+
+use accessors_rs::Accessors;
+
+use crate::component::{Boundary, Position};
+
+/// Number of nodes added in front of the starting position.
+pub const SNAKE_START_LENGTH: u16 = 10;
+
+#[derive(Clone)]
+pub struct Snake {
+    direction: Direction,
+    nodes: LinkedList<SnakeNode>,
+    /// Boundary the snake wrap around, it can leave the boundary when not set.
+    wrap_boundary: Option<Boundary>,
+}
+
+impl Snake {
+    pub fn new(head: SnakeNode, len: u16) -> Snake {
+        let direction = head.direction;
+        let nodes = LinkedList::from([head]);
+        let mut snake = Self {
+            direction,
+            nodes,
+            wrap_boundary: None,
+        };
+        for _ in 0..len {
+            snake.add_node_at_the_front()
+        }
+        snake
+    }
+
+    pub fn head(&self) -> &SnakeNode {
+        self.nodes.front().expect("Snake need at least one node.")
+    }
+
+    pub fn nodes(&self) -> &LinkedList<SnakeNode> {
+        &self.nodes
+    }
+
+    pub fn tail(&self) -> &SnakeNode {
+        self.nodes.back().expect("Snake need at least one node.")
+    }
+
+    pub fn eat(&mut self) {
+        self.add_node_at_the_back()
+    }
+
+    /// Remove nodes from the tail, the head and the node behind it are always kept.
+    pub fn shrink(&mut self, count: usize) {
+        let count = count.min(self.nodes.len().saturating_sub(2));
+        for _ in 0..count {
+            self.nodes.pop_back();
+        }
+    }
+
+    pub fn change_direction(&mut self, direction: Direction) -> bool {
+        let head_direction = self.head().direction;
+        let has_change = match direction {
+            Direction::Up => head_direction != Direction::Down,
+            Direction::Down => head_direction != Direction::Up,
+            Direction::Left => head_direction != Direction::Right,
+            Direction::Right => head_direction != Direction::Left,
+        };
+        if has_change {
+            self.direction = direction;
+        }
+        has_change
+    }
+
+    /// Move the head to the next position and return the old tail.
+    pub fn move_forward(&mut self) -> Option<SnakeNode> {
+        self.add_node_at_the_front();
+        self.nodes.pop_back()
+    }
+
+    pub fn is_biting_itself(&self) -> bool {
+        let mut p_map = HashSet::new();
+        for SnakeNode { position, .. } in &self.nodes {
+            if !p_map.insert(position) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Make the snake come back from the opposite edge of the boundary instead of leaving it.
+    pub fn set_wrap_boundary(&mut self, boundary: Option<Boundary>) {
+        self.wrap_boundary = boundary;
+    }
+
+    /// Position of the head after the next move.
+    ///
+    /// Without a wrap boundary, going past the top or the left of the screen wrap around
+    /// to the end of the `u16`, which is outside of any boundary.
+    pub fn get_next_position(&self) -> Position {
+        self.get_position_toward(self.head().position, self.direction)
+    }
+
+    fn add_node_at_the_front(&mut self) {
+        self.nodes
+            .push_front(SnakeNode::new(self.get_next_position(), self.direction));
+    }
+
+    fn add_node_at_the_back(&mut self) {
+        let tail = self.tail();
+        let direction = tail.direction;
+        let position = self.get_position_toward(tail.position, direction.opposite());
+        self.nodes.push_back(SnakeNode::new(position, direction))
+    }
+
+    /// Position next to the given one in the direction, on the opposite edge when it leave
+    /// the wrap boundary.
+    pub fn get_position_toward(&self, mut position: Position, direction: Direction) -> Position {
+        match direction {
+            Direction::Up => position.y = position.y.wrapping_sub(1),
+            Direction::Down => position.y = position.y.wrapping_add(1),
+            Direction::Left => position.x = position.x.wrapping_sub(1),
+            Direction::Right => position.x = position.x.wrapping_add(1),
+        }
+        match self.wrap_boundary {
+            Some(boundary) if !boundary.is_inside(position) => {
+                match direction {
+                    Direction::Up => position.y = boundary.bottom(),
+                    Direction::Down => position.y = boundary.top(),
+                    Direction::Left => position.x = boundary.right(),
+                    Direction::Right => position.x = boundary.left(),
+                }
+                position
+            }
+            _ => position,
+        }
+    }
+}
+
+#[derive(Debug, Accessors, Clone, PartialEq, Eq)]
+#[accessors(get_copy)]
+pub struct SnakeNode {
+    position: Position,
+    direction: Direction,
+}
+
+impl SnakeNode {
+    pub fn new(position: Position, direction: Direction) -> Self {
+        Self {
+            position,
+            direction,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// Name of the direction in the replays and in the agent protocol.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "up" => Direction::Up,
+            "down" => Direction::Down,
+            "left" => Direction::Left,
+            "right" => Direction::Right,
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+use rand::Rng;
+
+use crate::component::{Boundary, Position};
+
+/// Random cells tried before looking at every cell of the board.
+///
+/// When the board is nearly full, a random cell is unlikely to be free.
+const RANDOM_ATTEMPTS: usize = 32;
+
+/// How the cell of the next apple is chosen, only free cells are ever chosen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpawnStrategy {
+    /// Any free cell of the board.
+    Anywhere,
+    /// One of the spawn points that are free, any free cell of the board when none of them is.
+    SpawnPoints(Vec<Position>),
+}
+
+impl SpawnStrategy {
+    /// Random free cell of the board, none when every cell of the board is taken.
+    pub fn spawn(
+        &self,
+        board: Boundary,
+        is_free: impl Fn(Position) -> bool,
+        rng: &mut impl Rng,
+    ) -> Option<Position> {
+        if let SpawnStrategy::SpawnPoints(spawns) = self {
+            let free: Vec<Position> = spawns
+                .iter()
+                .copied()
+                .filter(|spawn| board.is_inside(*spawn) && is_free(*spawn))
+                .collect();
+            if !free.is_empty() {
+                return Some(free[rng.gen_range(0..free.len())]);
+            }
+        }
+
+        for _ in 0..RANDOM_ATTEMPTS {
+            let position = board.position() + board.dimension().get_random_position_inside(rng);
+            if is_free(position) {
+                return Some(position);
+            }
+        }
+        let free: Vec<Position> = (board.top()..=board.bottom())
+            .flat_map(|y| (board.left()..=board.right()).map(move |x| Position::new(x, y)))
+            .filter(|position| is_free(*position))
+            .collect();
+        if free.is_empty() {
+            None
+        } else {
+            Some(free[rng.gen_range(0..free.len())])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::SpawnStrategy;
+    use crate::{
+        component::{Boundary, Dimension, Position},
+        game_state::{fruit::FruitRates, GameState},
+        map::Map,
+        settings::{GameMode, Opponent},
+    };
+
+    /// Solo round on the `pillars` map, placed away from the top left of the screen.
+    fn state(seed: u64) -> GameState {
+        let board = Boundary::new(Position::new(3, 2), Dimension::new(80, 40));
+        let map = Map::find("pillars").unwrap();
+        GameState::new(
+            board,
+            seed,
+            GameMode::Classic,
+            Opponent::Solo,
+            Some(map.into()),
+            FruitRates::NONE,
+        )
+    }
+
+    fn snake_cells(state: &GameState) -> HashSet<Position> {
+        state
+            .snakes()
+            .flat_map(|snake| snake.nodes())
+            .map(|node| node.position())
+            .collect()
+    }
+
+    #[test]
+    fn never_spawn_on_a_snake_or_a_wall() {
+        for seed in 0..20 {
+            let mut state = state(seed);
+            assert!(!state.walls().is_empty());
+            let snake = snake_cells(&state);
+            for _ in 0..100 {
+                let position = state.random_position().unwrap();
+                assert!(state.boundary().is_inside(position));
+                assert!(!state.walls().contains(&position), "seed {seed}");
+                assert!(!snake.contains(&position), "seed {seed}");
+                assert_ne!(state.apple(), Some(position));
+            }
+        }
+    }
+
+    #[test]
+    fn find_the_last_free_cell() {
+        for seed in 0..20 {
+            let mut state = state(seed);
+            let board = state.boundary();
+            let last = Position::new(board.right(), board.bottom());
+            let snake = snake_cells(&state);
+            state.apple = None;
+            state.walls = (board.top()..=board.bottom())
+                .flat_map(|y| (board.left()..=board.right()).map(move |x| Position::new(x, y)))
+                .filter(|position| *position != last && !snake.contains(position))
+                .collect();
+
+            assert_eq!(state.random_position(), Some(last), "seed {seed}");
+            state.apple = Some(last);
+            assert_eq!(state.random_position(), None, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn spawn_on_the_free_spawn_points_first() {
+        let board = Boundary::new(Position::new(3, 2), Dimension::new(10, 5));
+        let spawns = vec![
+            Position::new(4, 2),
+            Position::new(5, 3),
+            Position::new(30, 30),
+        ];
+        let strategy = SpawnStrategy::SpawnPoints(spawns);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        for _ in 0..50 {
+            let position =
+                strategy.spawn(board, |position| position != Position::new(4, 2), &mut rng);
+            assert_eq!(position, Some(Position::new(5, 3)));
+        }
+        let is_free = |position: Position| position.x > 5;
+        for _ in 0..50 {
+            let position = strategy.spawn(board, is_free, &mut rng).unwrap();
+            assert!(board.is_inside(position) && is_free(position));
+        }
+    }
+}
@@ -0,0 +1,464 @@
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use accessors_rs::Accessors;
+use clap::ValueEnum;
+use error_chain::error_chain;
+
+use crate::{
+    component::Dimension,
+    game_state::fruit::FruitRates,
+    map::Map,
+    settings::{value_name, Difficulty, GameMode, SpeedCurve},
+};
+
+error_chain! {
+    errors {
+        UnsupportedVersion(header: String) {
+            description("Unsupported high score file version.")
+            display("Unsupported high score file version: '{}'", header)
+        }
+        InvalidLine(number: usize, line: String) {
+            description("Invalid line in the high score file.")
+            display("Invalid line {} in the high score file: '{}'", number, line)
+        }
+    }
+
+    foreign_links {
+        Io(std::io::Error);
+    }
+}
+
+const HEADER: &str = "snake-in-terminal high scores 1";
+/// Number of entries kept in the file.
+pub const MAX_HIGH_SCORES: usize = 10;
+
+/// Settings of a round, only the rounds played with the same settings are ranked together.
+#[derive(Debug, Clone, PartialEq, Eq, Accessors)]
+#[accessors(get_copy)]
+pub struct RoundSettings {
+    mode: GameMode,
+    difficulty: Difficulty,
+    speed_curve: SpeedCurve,
+    /// Name of the level played, none on the empty board.
+    #[accessors(get)]
+    level: Option<String>,
+    fruit_rates: FruitRates,
+}
+
+impl RoundSettings {
+    pub fn new(
+        mode: GameMode,
+        difficulty: Difficulty,
+        speed_curve: SpeedCurve,
+        map: Option<&Map>,
+        fruit_rates: FruitRates,
+    ) -> Self {
+        Self {
+            mode,
+            difficulty,
+            speed_curve,
+            level: map.map(|map| map.name().clone()),
+            fruit_rates,
+        }
+    }
+
+    /// Settings shown above a leaderboard, like `Classic, Normal, Constant, box`.
+    pub fn description(&self) -> String {
+        let mut description = format!(
+            "{}, {}, {}",
+            self.mode.name(),
+            self.difficulty.name(),
+            self.speed_curve.name()
+        );
+        if let Some(level) = &self.level {
+            description += &format!(", {level}");
+        }
+        if !self.fruit_rates.is_none() {
+            let FruitRates {
+                golden,
+                shrink,
+                slow_motion,
+                poison,
+            } = self.fruit_rates;
+            description += &format!(", fruits {golden}/{shrink}/{slow_motion}/{poison}%");
+        }
+        description
+    }
+}
+
+/// Result of a finished round.
+#[derive(Debug, Clone, Accessors)]
+#[accessors(get_copy)]
+pub struct HighScore {
+    score: u32,
+    /// Number of nodes of the snake at the end of the round.
+    length: usize,
+    elapsed: Duration,
+    /// Seconds since the Unix epoch when the round ended.
+    date: u64,
+    seed: u64,
+    board: Dimension,
+    #[accessors(get)]
+    settings: RoundSettings,
+}
+
+impl HighScore {
+    /// Entry for a round that ended now.
+    pub fn new(
+        score: u32,
+        length: usize,
+        elapsed: Duration,
+        seed: u64,
+        board: Dimension,
+        settings: RoundSettings,
+    ) -> Self {
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        Self {
+            score,
+            length,
+            elapsed,
+            date,
+            seed,
+            board,
+            settings,
+        }
+    }
+
+    /// Date of the end of the round as `YYYY-MM-DD` in UTC.
+    pub fn date_text(&self) -> String {
+        // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+        let days = (self.date / 86_400) as i64 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+
+    /// Elapsed time of the round as `MM:SS`.
+    pub fn elapsed_text(&self) -> String {
+        let seconds = self.elapsed.as_secs();
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+
+    /// The level is the last pair, its name is prefixed by its length since it can contain
+    /// spaces, like `level=9:two rooms`.
+    fn to_line(&self) -> String {
+        let settings = &self.settings;
+        let FruitRates {
+            golden,
+            shrink,
+            slow_motion,
+            poison,
+        } = settings.fruit_rates;
+        let mut line = format!(
+            "score={} length={} elapsed={} date={} seed={} board={}x{} \
+             mode={} difficulty={} speed_curve={} fruits={golden},{shrink},{slow_motion},{poison}",
+            self.score,
+            self.length,
+            self.elapsed.as_secs(),
+            self.date,
+            self.seed,
+            self.board.width,
+            self.board.height,
+            value_name(settings.mode),
+            value_name(settings.difficulty),
+            value_name(settings.speed_curve),
+        );
+        if let Some(level) = &settings.level {
+            line += &format!(" level={}:{level}", level.len());
+        }
+        line
+    }
+
+    /// Parse the `key=value` pairs of a line, unknown keys are ignored so new settings can be added.
+    ///
+    /// None when a pair is missing, only the level is optional.
+    fn from_line(line: &str) -> Option<Self> {
+        let (line, level) = match line.split_once(" level=") {
+            Some((line, level)) => {
+                let (length, name) = level.split_once(':')?;
+                if name.len() != length.parse::<usize>().ok()? {
+                    return None;
+                }
+                (line, Some(name.to_string()))
+            }
+            None => (line, None),
+        };
+        let (mut score, mut length, mut elapsed, mut date, mut seed, mut board) =
+            (None, None, None, None, None, None);
+        let (mut mode, mut difficulty, mut speed_curve, mut fruit_rates) = (None, None, None, None);
+        for pair in line.split_whitespace() {
+            let (key, value) = pair.split_once('=')?;
+            match key {
+                "score" => score = Some(value.parse().ok()?),
+                "length" => length = Some(value.parse().ok()?),
+                "elapsed" => elapsed = Some(Duration::from_secs(value.parse().ok()?)),
+                "date" => date = Some(value.parse().ok()?),
+                "seed" => seed = Some(value.parse().ok()?),
+                "board" => {
+                    let (width, height) = value.split_once('x')?;
+                    board = Some(Dimension::new(width.parse().ok()?, height.parse().ok()?));
+                }
+                "mode" => mode = Some(ValueEnum::from_str(value, false).ok()?),
+                "difficulty" => difficulty = Some(ValueEnum::from_str(value, false).ok()?),
+                "speed_curve" => speed_curve = Some(ValueEnum::from_str(value, false).ok()?),
+                "fruits" => {
+                    let mut rates = value.split(',').map(|rate| rate.parse().ok());
+                    let mut next = || rates.next().flatten();
+                    fruit_rates = Some(FruitRates {
+                        golden: next()?,
+                        shrink: next()?,
+                        slow_motion: next()?,
+                        poison: next()?,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Some(Self {
+            score: score?,
+            length: length?,
+            elapsed: elapsed?,
+            date: date?,
+            seed: seed?,
+            board: board?,
+            settings: RoundSettings {
+                mode: mode?,
+                difficulty: difficulty?,
+                speed_curve: speed_curve?,
+                level,
+                fruit_rates: fruit_rates?,
+            },
+        })
+    }
+}
+
+/// Best results, sorted from the highest score.
+#[derive(Debug, Default, Accessors)]
+#[accessors(get)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// `$XDG_DATA_HOME/snake-in-terminal/high_scores`, or `~/.local/share/...` when it is not set.
+    pub fn default_path() -> Option<PathBuf> {
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))?;
+        Some(data_home.join("snake-in-terminal").join("high_scores"))
+    }
+
+    /// Load the high scores, a missing file is an empty table.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default())
+            }
+            Err(error) => return Err(error.into()),
+        };
+        let mut lines = content.lines().enumerate().map(|(i, line)| (i + 1, line));
+        // The lines are not trimmed, the name of a level can end with a space.
+        let header = lines
+            .next()
+            .map(|(_, line)| line.trim())
+            .unwrap_or_default();
+        if header != HEADER {
+            return Err(ErrorKind::UnsupportedVersion(header.to_string()).into());
+        }
+        let mut high_scores = Self::default();
+        for (number, line) in lines.filter(|(_, line)| !line.trim().is_empty()) {
+            let high_score = HighScore::from_line(line)
+                .ok_or_else(|| ErrorKind::InvalidLine(number, line.to_string()))?;
+            high_scores.insert(high_score);
+        }
+        Ok(high_scores)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut content = format!("{HEADER}\n");
+        for high_score in &self.entries {
+            content += &high_score.to_line();
+            content.push('\n');
+        }
+        Ok(write_file(path.as_ref(), &content)?)
+    }
+
+    /// Entries of the rounds played with the settings, from the highest score.
+    pub fn with_settings<'a>(
+        &'a self,
+        settings: &'a RoundSettings,
+    ) -> impl Iterator<Item = &'a HighScore> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.settings == *settings)
+    }
+
+    /// Insert the entry after the ones with the same score and return its rank among
+    /// the entries with the same settings.
+    ///
+    /// Only the best `MAX_HIGH_SCORES` entries of each settings are kept, so the rank can be
+    /// past the last of them.
+    pub fn insert(&mut self, high_score: HighScore) -> usize {
+        let index = self
+            .entries
+            .partition_point(|entry| entry.score >= high_score.score);
+        let rank = self.entries[..index]
+            .iter()
+            .filter(|entry| entry.settings == high_score.settings)
+            .count();
+        self.entries.insert(index, high_score);
+        for entry in std::mem::take(&mut self.entries) {
+            if self.with_settings(&entry.settings).count() < MAX_HIGH_SCORES {
+                self.entries.push(entry);
+            }
+        }
+        rank
+    }
+}
+
+/// Write the content in a temporary file then rename it, so the file is never left half written.
+///
+/// The directory of the file is created when it does not exist.
+pub fn write_file(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let mut file = fs::File::create(&temporary)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn high_score(score: u32, settings: RoundSettings) -> HighScore {
+        HighScore::new(
+            score,
+            4,
+            Duration::from_secs(65),
+            7,
+            Dimension::new(40, 20),
+            settings,
+        )
+    }
+
+    fn classic_settings() -> RoundSettings {
+        RoundSettings::new(
+            GameMode::Classic,
+            Difficulty::Normal,
+            SpeedCurve::Constant,
+            None,
+            FruitRates::NONE,
+        )
+    }
+
+    fn wrap_settings() -> RoundSettings {
+        wrap_settings_on(" level=two rooms ")
+    }
+
+    fn wrap_settings_on(level: &str) -> RoundSettings {
+        RoundSettings::new(
+            GameMode::Wrap,
+            Difficulty::Hard,
+            SpeedCurve::Score,
+            Some(&Map::new(level, Dimension::new(40, 20))),
+            FruitRates {
+                golden: 5,
+                shrink: 10,
+                slow_motion: 0,
+                poison: 20,
+            },
+        )
+    }
+
+    #[test]
+    fn read_the_line_written() {
+        let written = high_score(12, wrap_settings());
+        let read = HighScore::from_line(&written.to_line()).unwrap();
+        assert_eq!(read.score(), 12);
+        assert_eq!(read.elapsed(), Duration::from_secs(65));
+        assert_eq!(read.board(), Dimension::new(40, 20));
+        assert_eq!(read.settings(), &wrap_settings());
+    }
+
+    #[test]
+    fn read_the_file_written() {
+        let path = env::temp_dir().join(format!("snake-high-scores-{}", std::process::id()));
+        let mut high_scores = HighScores::default();
+        high_scores.insert(high_score(12, wrap_settings()));
+        high_scores.insert(high_score(7, classic_settings()));
+
+        high_scores.save(&path).unwrap();
+        let read = HighScores::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        let read = read.unwrap();
+        assert_eq!(read.entries().len(), 2);
+        assert_eq!(read.entries()[0].settings(), &wrap_settings());
+        assert_eq!(read.entries()[1].settings(), &classic_settings());
+    }
+
+    #[test]
+    fn refuse_a_line_without_its_settings() {
+        let line = high_score(3, classic_settings()).to_line();
+        assert!(HighScore::from_line(&line).is_some());
+        for key in ["score", "mode", "difficulty", "speed_curve", "fruits"] {
+            let without_key: Vec<&str> = line
+                .split(' ')
+                .filter(|pair| !pair.starts_with(&format!("{key}=")))
+                .collect();
+            assert!(
+                HighScore::from_line(&without_key.join(" ")).is_none(),
+                "{key}"
+            );
+        }
+    }
+
+    #[test]
+    fn refuse_a_level_of_another_length() {
+        let line = high_score(3, wrap_settings_on("box")).to_line();
+        assert!(line.ends_with(" level=3:box"));
+        assert!(HighScore::from_line(&line.replace("level=3:", "level=4:")).is_none());
+    }
+
+    #[test]
+    fn rank_the_entries_of_each_settings_apart() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.insert(high_score(10, classic_settings())), 0);
+        assert_eq!(high_scores.insert(high_score(20, wrap_settings())), 0);
+        assert_eq!(high_scores.insert(high_score(5, classic_settings())), 1);
+        for _ in 0..MAX_HIGH_SCORES {
+            high_scores.insert(high_score(1, wrap_settings()));
+        }
+        assert_eq!(
+            high_scores.with_settings(&wrap_settings()).count(),
+            MAX_HIGH_SCORES
+        );
+        assert_eq!(high_scores.with_settings(&classic_settings()).count(), 2);
+    }
+}
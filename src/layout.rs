@@ -1,4 +1,7 @@
-use std::{io::Stdout, sync::Arc};
+use std::{
+    io::{Stdout, Write},
+    sync::Arc,
+};
 
 use snake_in_terminal::terminus::{
     screen::SharedScreen,
@@ -6,39 +9,44 @@ use snake_in_terminal::terminus::{
 };
 use tokio::sync::Mutex;
 
-use crate::component::{
-    self,
-    border::BorderComponent,
-    game_board::GameBoardComponent,
-    game_over::{GameOverComponent, GAME_OVER_HEIGHT, GAME_OVER_WIDTH},
-    timer::TimerComponent,
-    Boundary, Dimension, Position,
+use crate::{
+    component::{
+        self,
+        border::BorderComponent,
+        demo::{DemoComponent, DEMO_TEXT},
+        game_board::GameBoardComponent,
+        game_over::{GameOverComponent, GAME_OVER_HEIGHT, GAME_OVER_WIDTH},
+        pause::{PauseComponent, PAUSE_HEIGHT, PAUSE_WIDTH},
+        timer::TimerComponent,
+        title::{TITLE_HEIGHT, TITLE_WIDTH},
+        Boundary, Dimension, Position,
+    },
+    computer::hamiltonian::CycleFollower,
+    game_state::{fruit::FruitRates, GameState},
+    map::Map,
+    settings::{GameMode, Opponent},
 };
 
 pub fn create_application_timer(
     screen: SharedScreen<Stdout>,
 ) -> component::Result<Arc<Mutex<TimerComponent>>> {
     let screen_dimension: Dimension = screen.lock()?.size().into();
-    let position = Position {
-        x: screen_dimension.width - 12,
-        y: 1,
-    };
-    let timer = TimerComponent::new(screen, position);
+    let timer = TimerComponent::new(screen, get_timer_position(screen_dimension));
     timer.render()?;
     let timer = Arc::new(Mutex::new(timer));
     Ok(timer)
 }
 
-pub fn create_application_border(
-    screen: SharedScreen<Stdout>,
-) -> component::Result<BorderComponent> {
-    let screen_dimension: Dimension = screen.lock()?.size().into();
-    let boundary = Boundary::new(
-        Position::new(1, 2),
-        Dimension::new(screen_dimension.width, screen_dimension.height - 1),
-    );
+/// Border around the game board.
+pub fn create_application_border<T: Write + Send>(
+    screen: SharedScreen<T>,
+    game_board_boundary: Boundary,
+    mode: GameMode,
+) -> component::Result<BorderComponent<T>> {
+    let boundary = get_border_boundary(game_board_boundary);
     let style = Style::from([StyleProperty::Color(Color::RGB(255, 255, 255))]);
-    let border = BorderComponent::new(screen, boundary, style);
+    let mut border = BorderComponent::new(screen, boundary, style);
+    border.set_open(mode == GameMode::Wrap);
     border.render()?;
     Ok(border)
 }
@@ -47,26 +55,140 @@ pub fn create_application_game_board(
     screen: SharedScreen<Stdout>,
 ) -> component::Result<Arc<Mutex<GameBoardComponent>>> {
     let screen_dimension: Dimension = screen.lock()?.size().into();
-    let game_board = GameBoardComponent::new(screen, get_game_board_boundary(screen_dimension))?;
+    let game_board = GameBoardComponent::new(screen, get_game_board_boundary(screen_dimension));
     let game_board = Arc::new(Mutex::new(game_board));
     Ok(game_board)
 }
 
+pub fn create_application_game_state(
+    screen: SharedScreen<Stdout>,
+    seed: u64,
+    mode: GameMode,
+    opponent: Opponent,
+    map: Option<Arc<Map>>,
+    fruit_rates: FruitRates,
+) -> component::Result<Arc<Mutex<GameState>>> {
+    let screen_dimension: Dimension = screen.lock()?.size().into();
+    let game_state = GameState::new(
+        get_game_board_boundary(screen_dimension),
+        seed,
+        mode,
+        opponent,
+        map,
+        fruit_rates,
+    );
+    Ok(Arc::new(Mutex::new(game_state)))
+}
+
+/// Round played by the demo, on an empty board with a cycle over it.
+pub fn create_application_demo_game_state(
+    screen: SharedScreen<Stdout>,
+    seed: u64,
+) -> component::Result<Arc<Mutex<GameState>>> {
+    let screen_dimension: Dimension = screen.lock()?.size().into();
+    let game_state = GameState::new(
+        CycleFollower::board_for(get_game_board_boundary(screen_dimension)),
+        seed,
+        GameMode::Classic,
+        Opponent::Solo,
+        None,
+        FruitRates::NONE,
+    );
+    Ok(Arc::new(Mutex::new(game_state)))
+}
+
 pub fn create_application_game_over_message(
     screen: SharedScreen<Stdout>,
 ) -> component::Result<Arc<Mutex<GameOverComponent>>> {
     let screen_dimension: Dimension = screen.lock()?.size().into();
-    let position = Position::new(
-        (screen_dimension.width - GAME_OVER_WIDTH) / 2,
-        (screen_dimension.height - GAME_OVER_HEIGHT) / 2,
-    );
+    let position = get_game_over_position(screen_dimension);
     let game_over = Arc::new(Mutex::new(GameOverComponent::new(screen, position)));
     Ok(game_over)
 }
 
-fn get_game_board_boundary(screen_dimension: Dimension) -> Boundary {
+pub fn create_application_pause(screen: SharedScreen<Stdout>) -> component::Result<PauseComponent> {
+    let screen_dimension: Dimension = screen.lock()?.size().into();
+    let position = get_pause_position(screen_dimension);
+    Ok(PauseComponent::new(screen, position))
+}
+
+pub fn create_application_demo(screen: SharedScreen<Stdout>) -> component::Result<DemoComponent> {
+    let screen_dimension: Dimension = screen.lock()?.size().into();
+    Ok(DemoComponent::new(
+        screen,
+        get_demo_position(screen_dimension),
+    ))
+}
+
+/// The demo line is centered on the line of the score and the timer.
+pub fn get_demo_position(screen_dimension: Dimension) -> Position {
+    Position::new(
+        (screen_dimension
+            .width
+            .saturating_sub(DEMO_TEXT.len() as u16)
+            / 2)
+        .max(1),
+        1,
+    )
+}
+
+pub fn get_timer_position(screen_dimension: Dimension) -> Position {
+    Position::new(screen_dimension.width.saturating_sub(12).max(1), 1)
+}
+
+/// Boundary of the border drawn around the game board, which can be smaller than the screen.
+pub fn get_border_boundary(game_board_boundary: Boundary) -> Boundary {
+    let Position { x, y } = game_board_boundary.position();
+    let Dimension { width, height } = game_board_boundary.dimension();
+    Boundary::new(
+        Position::new(x.saturating_sub(1).max(1), y.saturating_sub(1).max(1)),
+        Dimension::new(width + 2, height + 2),
+    )
+}
+
+pub fn get_game_over_position(screen_dimension: Dimension) -> Position {
+    Position::new(
+        (screen_dimension.width.saturating_sub(GAME_OVER_WIDTH) / 2).max(1),
+        (screen_dimension.height.saturating_sub(GAME_OVER_HEIGHT) / 2).max(1),
+    )
+}
+
+pub fn get_pause_position(screen_dimension: Dimension) -> Position {
+    Position::new(
+        (screen_dimension.width.saturating_sub(PAUSE_WIDTH) / 2).max(1),
+        (screen_dimension.height.saturating_sub(PAUSE_HEIGHT) / 2).max(1),
+    )
+}
+
+pub fn get_game_board_boundary(screen_dimension: Dimension) -> Boundary {
     Boundary::new(
         Position::new(2, 3),
-        Dimension::new(screen_dimension.width - 2, screen_dimension.height - 3),
+        Dimension::new(
+            screen_dimension.width.saturating_sub(2).max(1),
+            screen_dimension.height.saturating_sub(3).max(1),
+        ),
+    )
+}
+
+/// Position of the title and of what is under it, both centered on the screen.
+pub fn get_title_and_content_positions(
+    screen_dimension: Dimension,
+    content_dimension: Dimension,
+) -> (Position, Position) {
+    let height = TITLE_HEIGHT + 2 + content_dimension.height;
+    let y = (screen_dimension.height.saturating_sub(height) / 2).max(1);
+    (
+        Position::new(
+            (screen_dimension.width.saturating_sub(TITLE_WIDTH) / 2).max(1),
+            y,
+        ),
+        Position::new(
+            (screen_dimension
+                .width
+                .saturating_sub(content_dimension.width)
+                / 2)
+            .max(1),
+            y + TITLE_HEIGHT + 2,
+        ),
     )
 }
@@ -1,63 +1,135 @@
+mod agent;
+mod application;
+mod bot;
+mod cli;
 mod component;
+mod computer;
+mod controller;
 mod game_input;
+mod game_state;
+mod high_score;
 mod layout;
+mod map;
+mod map_editor;
+mod replay;
+mod settings;
 mod snake_game;
+mod tournament;
 
 use std::io;
 
-use component::snake::Direction;
-use snake_game::SnakeGame;
-use snake_in_terminal::terminus::screen::{Screen, SharedScreen};
+use agent::Agent;
+use application::Application;
+use clap::Parser;
+use cli::{Cli, Command};
+use map::Map;
+use replay::Replay;
+use snake_in_terminal::terminus::{
+    screen::{Screen, SharedScreen},
+    session::TerminalSession,
+};
 use tokio::sync::mpsc;
+use tournament::Tournament;
 
 #[tokio::main]
 async fn main() {
+    let mut cli = Cli::parse();
+    let replay = match cli.replay.as_ref().map(Replay::load).transpose() {
+        Ok(replay) => replay,
+        Err(error) => {
+            println!("{:?}", error.to_string());
+            return;
+        }
+    };
+
+    // The map edited, or the level selected at the start.
+    let map = match &cli.command {
+        Some(Command::Edit { path }) if path.exists() => Map::load(path).map(Some),
+        Some(Command::Edit { .. }) => Ok(None),
+        Some(Command::Tournament(_) | Command::Agent(_)) | None => {
+            cli.level.as_deref().map(Map::find).transpose()
+        }
+    };
+    let map = match map {
+        Ok(map) => map,
+        Err(error) => {
+            println!("{:?}", error.to_string());
+            return;
+        }
+    };
+
+    // The tournament is played without the terminal, its ranking is printed once it is over.
+    let command = cli.command.take();
+    if let Some(Command::Tournament(args)) = command {
+        let tournament = Tournament::new(&cli, &args, map);
+        let bots = match tournament.bots(&args.bots) {
+            Ok(bots) => bots,
+            Err(error) => {
+                println!("{:?}", error.to_string());
+                return;
+            }
+        };
+        let standings = tournament.run(&bots);
+        print!("{}", tournament::ranking_table(&standings));
+        if let Some(path) = &args.output {
+            if let Err(error) = tournament::save(&standings, path, args.format) {
+                println!("{:?}", error.to_string());
+            }
+        }
+        return;
+    }
+    // The agent owns stdin and stdout, so its errors go to stderr.
+    if let Some(Command::Agent(args)) = command {
+        let result = Agent::new(&cli, &args, map)
+            .and_then(|mut agent| agent.run(io::stdin().lock(), io::stdout().lock()));
+        if let Err(error) = result {
+            eprintln!("{:?}", error.to_string());
+        }
+        return;
+    }
+    cli.command = command;
+
+    let session = match TerminalSession::start() {
+        Ok(session) => session,
+        Err(error) => {
+            println!("{:?}", error.to_string());
+            return;
+        }
+    };
+
     let (game_tx, mut game_rx) = mpsc::unbounded_channel();
     let rendering_handle = tokio::spawn(async move {
         let mut screen = Screen::new(io::stdout);
         screen.clear_screen()?;
-        screen.cursor_mut().hide()?;
         let shared_screen = SharedScreen::new(screen);
-        let mut snake_game = SnakeGame::new(
-            layout::create_application_timer(SharedScreen::clone(&shared_screen))?,
-            layout::create_application_game_board(SharedScreen::clone(&shared_screen))?,
-            layout::create_application_game_over_message(SharedScreen::clone(&shared_screen))?,
-            layout::create_application_border(SharedScreen::clone(&shared_screen))?,
-        );
-        snake_game.start_game_loop().await;
-
-        while let Some(input) = game_rx.recv().await {
-            if let Some(direction) = match input {
-                game_input::GameInput::Up => Some(Direction::Up),
-                game_input::GameInput::Down => Some(Direction::Down),
-                game_input::GameInput::Left => Some(Direction::Left),
-                game_input::GameInput::Right => Some(Direction::Right),
-                _ => None,
-            } {
-                snake_game
-                    .game_board_mut()
-                    .lock()
-                    .await
-                    .snake_component_mut()
-                    .snake_mut()
-                    .change_direction(direction);
-            }
-            match input {
-                game_input::GameInput::Quit => break,
-                _ => {}
+        let mut application = Application::new(shared_screen, cli, replay, map).await?;
+
+        loop {
+            match tokio::time::timeout(application::IDLE_DELAY, game_rx.recv()).await {
+                Ok(Some(input)) => {
+                    if !application.handle_input(input).await? {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => application.idle().await?,
             }
         }
-        let mut screen = shared_screen.lock()?;
-        screen.erase_screen()?;
-        screen.cursor_mut().show()?;
         component::Result::Ok(())
     });
 
-    if let Err(error) = game_input::read_inputs(game_tx) {
+    let input_result = game_input::read_inputs(game_tx);
+    let rendering_result = rendering_handle.await;
+    // Restore the terminal first, errors printed in the alternative screen would be lost.
+    drop(session);
+
+    if let Err(error) = input_result {
         println!("{:?}", error.to_string());
     }
 
-    if let Err(error) = rendering_handle.await {
-        println!("{error:?}");
+    match rendering_result {
+        Ok(Err(error)) => println!("{:?}", error.to_string()),
+        Err(error) => println!("{error:?}"),
+        Ok(Ok(())) => {}
     }
 }
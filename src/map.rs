@@ -0,0 +1,423 @@
+use std::{collections::HashSet, fmt, fs, path::Path, str::FromStr};
+
+use accessors_rs::Accessors;
+use error_chain::error_chain;
+
+use crate::{
+    component::{Boundary, Dimension, Position},
+    game_state::snake::{Direction, SnakeNode, SNAKE_START_LENGTH},
+};
+
+error_chain! {
+    errors {
+        UnsupportedVersion(header: String) {
+            description("Unsupported map version.")
+            display("Unsupported map version: '{}'", header)
+        }
+        InvalidLine(number: usize, line: String) {
+            description("Invalid line in the map.")
+            display("Invalid line {} in the map: '{}'", number, line)
+        }
+        MissingStart {
+            description("The map has no snake.")
+            display("The map has no snake, mark where its tail start with one of '>', '<', '^' or 'v'.")
+        }
+        BlockedStart {
+            description("The snake does not fit in the map.")
+            display("The snake need {} free cells from its start toward its direction.", SNAKE_START_LENGTH + 1)
+        }
+        UnknownMap(name: String) {
+            description("Unknown map.")
+            display("'{}' is neither a built-in map nor a map file.", name)
+        }
+    }
+
+    foreign_links {
+        Io(std::io::Error);
+    }
+}
+
+/// First line of every map file, the number is incremented when the format change.
+const HEADER: &str = "snake-in-terminal map";
+const VERSION: u32 = 1;
+
+const WALL: char = '#';
+const EMPTY: char = '.';
+const APPLE_SPAWN: char = '*';
+
+/// Maps shipped with the game, by the name used to select them.
+const BUILT_IN_MAPS: [(&str, &str); 4] = [
+    ("box", include_str!("../maps/box.txt")),
+    ("cross", include_str!("../maps/cross.txt")),
+    ("pillars", include_str!("../maps/pillars.txt")),
+    ("rooms", include_str!("../maps/rooms.txt")),
+];
+
+/// Board of a level, with its walls and where the snake and the apples start.
+///
+/// Every position is relative to the top left cell of the map, which is 0, 0.
+///
+/// In a file, each cell of a row is one character: `#` for a wall, `.` or a space for
+/// an empty cell, `*` for a cell where the apples can spawn, and one of `>`, `<`, `^` or `v`
+/// where the tail of the snake start and the direction it head to.
+/// When the map has no apple spawn, the apples spawn anywhere.
+#[derive(Debug, Clone, PartialEq, Eq, Accessors)]
+#[accessors(get)]
+pub struct Map {
+    name: String,
+    #[accessors(get_copy)]
+    dimension: Dimension,
+    walls: HashSet<Position>,
+    start: SnakeNode,
+    apple_spawns: Vec<Position>,
+}
+
+impl Map {
+    /// Map without walls, the tail of the snake start at the top left cell and head to the right.
+    pub fn new(name: &str, dimension: Dimension) -> Self {
+        Self {
+            name: name.to_string(),
+            dimension,
+            walls: HashSet::new(),
+            start: SnakeNode::new(Position::new(0, 0), Direction::Right),
+            apple_spawns: Vec::new(),
+        }
+    }
+
+    /// Read a map from its rows, which are the lines of a map file after its name.
+    pub fn from_rows<'a>(
+        name: &str,
+        rows: impl IntoIterator<Item = (usize, &'a str)>,
+    ) -> Result<Self> {
+        let mut walls = HashSet::new();
+        let mut start = None;
+        let mut apple_spawns = Vec::new();
+        let mut dimension = Dimension::new(0, 0);
+        for (y, (number, row)) in rows.into_iter().enumerate() {
+            let invalid_line = || ErrorKind::InvalidLine(number, row.to_string());
+            for (x, cell) in row.chars().enumerate() {
+                let position = Position::new(x as u16, y as u16);
+                match cell {
+                    WALL => {
+                        walls.insert(position);
+                    }
+                    EMPTY | ' ' => {}
+                    APPLE_SPAWN => apple_spawns.push(position),
+                    _ => {
+                        let direction = parse_direction(cell).ok_or_else(invalid_line)?;
+                        if start.is_some() {
+                            return Err(invalid_line().into());
+                        }
+                        start = Some(SnakeNode::new(position, direction));
+                    }
+                }
+            }
+            dimension.width = dimension.width.max(row.chars().count() as u16);
+            dimension.height = y as u16 + 1;
+        }
+
+        let map = Self {
+            name: name.to_string(),
+            dimension,
+            walls,
+            start: start.ok_or(ErrorKind::MissingStart)?,
+            apple_spawns,
+        };
+        if !map.can_hold_snake() {
+            return Err(ErrorKind::BlockedStart.into());
+        }
+        Ok(map)
+    }
+
+    /// Map shipped with the game, or the one in the file when no built-in map has the name.
+    pub fn find(name: &str) -> Result<Self> {
+        match BUILT_IN_MAPS.iter().find(|(built_in, _)| *built_in == name) {
+            Some((_, map)) => map.parse(),
+            None if Path::new(name).is_file() => Self::load(name),
+            None => Err(ErrorKind::UnknownMap(name.to_string()).into()),
+        }
+    }
+
+    /// Every map shipped with the game.
+    pub fn built_in() -> Vec<Self> {
+        BUILT_IN_MAPS
+            .iter()
+            .map(|(_, map)| map.parse().expect("Built-in maps are valid."))
+            .collect()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Save the map, unless the snake cannot start in it.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        if !self.can_hold_snake() {
+            return Err(ErrorKind::BlockedStart.into());
+        }
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    /// Add or remove a wall, a wall replace the apple spawn of its cell.
+    pub fn toggle_wall(&mut self, position: Position) {
+        if !self.walls.remove(&position) {
+            self.apple_spawns.retain(|spawn| *spawn != position);
+            self.walls.insert(position);
+        }
+    }
+
+    /// Add or remove an apple spawn, an apple spawn replace the wall of its cell.
+    pub fn toggle_apple_spawn(&mut self, position: Position) {
+        if self.apple_spawns.contains(&position) {
+            self.apple_spawns.retain(|spawn| *spawn != position);
+        } else {
+            self.walls.remove(&position);
+            self.apple_spawns.push(position);
+        }
+    }
+
+    /// Place where the tail of the snake start and the direction it head to.
+    pub fn set_start(&mut self, start: SnakeNode) {
+        self.start = start;
+    }
+
+    /// The rows of the map, as written in a map file.
+    pub fn rows(&self) -> Vec<String> {
+        (0..self.dimension.height)
+            .map(|y| {
+                (0..self.dimension.width)
+                    .map(|x| self.cell(Position::new(x, y)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Walls of the map placed on the game board.
+    pub fn walls_on(&self, board: Boundary) -> HashSet<Position> {
+        self.walls
+            .iter()
+            .map(|wall| board.position() + *wall)
+            .collect()
+    }
+
+    /// Cells where the apples can spawn placed on the game board.
+    pub fn apple_spawns_on(&self, board: Boundary) -> Vec<Position> {
+        self.apple_spawns
+            .iter()
+            .map(|spawn| board.position() + *spawn)
+            .collect()
+    }
+
+    /// Starting node of the snake placed on the game board.
+    pub fn start_on(&self, board: Boundary) -> SnakeNode {
+        SnakeNode::new(
+            board.position() + self.start.position(),
+            self.start.direction(),
+        )
+    }
+
+    fn cell(&self, position: Position) -> char {
+        if self.walls.contains(&position) {
+            WALL
+        } else if self.start.position() == position {
+            direction_symbol(self.start.direction())
+        } else if self.apple_spawns.contains(&position) {
+            APPLE_SPAWN
+        } else {
+            EMPTY
+        }
+    }
+
+    /// True when the cells the snake start on are inside the map and free.
+    pub fn can_hold_snake(&self) -> bool {
+        let Position { mut x, mut y } = self.start.position();
+        for _ in 0..=SNAKE_START_LENGTH {
+            if x >= self.dimension.width
+                || y >= self.dimension.height
+                || self.walls.contains(&Position::new(x, y))
+            {
+                return false;
+            }
+            match self.start.direction() {
+                Direction::Up => y = y.wrapping_sub(1),
+                Direction::Down => y += 1,
+                Direction::Left => x = x.wrapping_sub(1),
+                Direction::Right => x += 1,
+            }
+        }
+        true
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER} {VERSION}")?;
+        writeln!(f, "name {}", self.name)?;
+        for row in self.rows() {
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Map {
+    type Err = Error;
+
+    /// Parse a map, the rows start after the name and end at the first empty line.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim_end()));
+
+        let header = lines.next().map(|(_, line)| line).unwrap_or_default();
+        if header != format!("{HEADER} {VERSION}") {
+            return Err(ErrorKind::UnsupportedVersion(header.to_string()).into());
+        }
+
+        let (number, line) = lines.next().unwrap_or_default();
+        let name = line
+            .strip_prefix("name ")
+            .map(str::trim)
+            .ok_or_else(|| ErrorKind::InvalidLine(number, line.to_string()))?;
+        Self::from_rows(name, lines.take_while(|(_, line)| !line.is_empty()))
+    }
+}
+
+fn direction_symbol(direction: Direction) -> char {
+    match direction {
+        Direction::Up => '^',
+        Direction::Down => 'v',
+        Direction::Left => '<',
+        Direction::Right => '>',
+    }
+}
+
+fn parse_direction(symbol: char) -> Option<Direction> {
+    Some(match symbol {
+        '^' => Direction::Up,
+        'v' => Direction::Down,
+        '<' => Direction::Left,
+        '>' => Direction::Right,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROWS: [&str; 4] = [
+        "##############",
+        "#>...........#",
+        "#....*..#....#",
+        "##############",
+    ];
+
+    fn from_rows(rows: &[&str]) -> Result<Map> {
+        Map::from_rows("test", rows.iter().copied().enumerate())
+    }
+
+    #[test]
+    fn read_the_rows() {
+        let map = from_rows(&ROWS).unwrap();
+        assert_eq!(map.dimension(), Dimension::new(14, 4));
+        assert_eq!(
+            map.start(),
+            &SnakeNode::new(Position::new(1, 1), Direction::Right)
+        );
+        assert_eq!(map.apple_spawns(), &vec![Position::new(5, 2)]);
+        assert!(map.walls().contains(&Position::new(8, 2)));
+        assert_eq!(map.rows(), ROWS);
+    }
+
+    #[test]
+    fn read_the_map_written() {
+        let map = from_rows(&ROWS).unwrap();
+        let read: Map = map.to_string().parse().unwrap();
+        assert_eq!(read, map);
+    }
+
+    #[test]
+    fn pad_the_short_rows() {
+        let map = from_rows(&["v", "", "  #", "", "", "", "", "", "", "", "", ""]).unwrap();
+        assert_eq!(map.dimension(), Dimension::new(3, 12));
+        assert_eq!(map.rows()[0], "v..");
+        assert_eq!(map.rows()[2], "..#");
+    }
+
+    #[test]
+    fn refuse_an_unknown_cell() {
+        let mut rows = ROWS;
+        rows[2] = "#....*..X....#";
+        assert!(matches!(
+            from_rows(&rows),
+            Err(Error(ErrorKind::InvalidLine(2, line), _)) if line == rows[2]
+        ));
+    }
+
+    #[test]
+    fn refuse_a_second_start() {
+        let mut rows = ROWS;
+        rows[2] = "#....*..<....#";
+        assert!(matches!(
+            from_rows(&rows),
+            Err(Error(ErrorKind::InvalidLine(2, _), _))
+        ));
+    }
+
+    #[test]
+    fn refuse_a_map_without_start() {
+        let mut rows = ROWS;
+        rows[1] = "#............#";
+        assert!(matches!(
+            from_rows(&rows),
+            Err(Error(ErrorKind::MissingStart, _))
+        ));
+    }
+
+    #[test]
+    fn refuse_a_blocked_start() {
+        for row in ["#>.......#...#", "#.....>......#"] {
+            let mut rows = ROWS;
+            rows[1] = row;
+            assert!(matches!(
+                from_rows(&rows),
+                Err(Error(ErrorKind::BlockedStart, _))
+            ));
+        }
+    }
+
+    #[test]
+    fn hold_the_snake_on_free_cells_inside_the_map() {
+        let mut map = Map::new("test", Dimension::new(SNAKE_START_LENGTH + 1, 1));
+        assert!(map.can_hold_snake());
+
+        map.set_start(SnakeNode::new(
+            Position::new(SNAKE_START_LENGTH, 0),
+            Direction::Left,
+        ));
+        assert!(map.can_hold_snake());
+
+        map.set_start(SnakeNode::new(Position::new(0, 0), Direction::Left));
+        assert!(!map.can_hold_snake());
+
+        map.set_start(SnakeNode::new(Position::new(0, 0), Direction::Down));
+        assert!(!map.can_hold_snake());
+
+        map.set_start(SnakeNode::new(Position::new(0, 0), Direction::Right));
+        map.toggle_wall(Position::new(SNAKE_START_LENGTH, 0));
+        assert!(!map.can_hold_snake());
+    }
+
+    #[test]
+    fn parse_every_built_in_map() {
+        for map in Map::built_in() {
+            assert!(map.can_hold_snake(), "{}", map.name());
+            assert_eq!(
+                Map::find(map.name().to_lowercase().as_str()).ok(),
+                Some(map)
+            );
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::{io::Stdout, path::PathBuf};
+
+use accessors_rs::Accessors;
+use snake_in_terminal::terminus::screen::SharedScreen;
+
+use crate::{
+    component::{
+        self, border::BorderComponent, map_board::MapBoardComponent,
+        status_bar::StatusBarComponent, Boundary, Dimension, Position,
+    },
+    game_state::snake::{Direction, SnakeNode},
+    layout,
+    map::Map,
+    settings::GameMode,
+};
+
+const KEY_BINDINGS: &str = " ←↑↓→ move  w/space wall  a apple  s snake  enter save  q back ";
+
+/// Edit a map file with a cursor over the cells of the map.
+#[derive(Accessors)]
+pub struct MapEditor {
+    screen: SharedScreen<Stdout>,
+    border: BorderComponent,
+    map_board: MapBoardComponent,
+    status_bar: StatusBarComponent,
+    map: Map,
+    path: PathBuf,
+    /// Cell of the map under the cursor, 0, 0 is the top left cell.
+    cursor: Position,
+    /// Result of the last save, shown in the status bar.
+    message: String,
+    /// Map as it is in its file, none when the file was not saved yet.
+    #[accessors(get)]
+    saved_map: Option<Map>,
+}
+
+impl MapEditor {
+    /// Edit the map of the file, a new map as big as the game board is created when there is none.
+    pub fn new(
+        screen: SharedScreen<Stdout>,
+        path: PathBuf,
+        map: Option<Map>,
+    ) -> component::Result<Self> {
+        let screen_dimension: Dimension = screen.lock()?.size().into();
+        let game_board_boundary = layout::get_game_board_boundary(screen_dimension);
+        let saved_map = map.clone();
+        let map = map.unwrap_or_else(|| {
+            let name = path
+                .file_stem()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Map::new(&name, game_board_boundary.dimension())
+        });
+        let board = Boundary::new(game_board_boundary.position(), map.dimension());
+        Ok(Self {
+            border: layout::create_application_border(
+                SharedScreen::clone(&screen),
+                board,
+                GameMode::Classic,
+            )?,
+            map_board: MapBoardComponent::new(SharedScreen::clone(&screen)),
+            status_bar: StatusBarComponent::new(SharedScreen::clone(&screen), 1),
+            screen,
+            map,
+            path,
+            cursor: Position::new(0, 0),
+            message: String::new(),
+            saved_map,
+        })
+    }
+
+    pub fn render(&self) -> component::Result<()> {
+        self.map_board
+            .render(&self.map, self.board()?, self.cursor)?;
+        self.border.render()?;
+        self.status_bar.render(KEY_BINDINGS, &self.message)?;
+        self.screen.lock()?.present()?;
+        Ok(())
+    }
+
+    /// Draw everything again on the resized screen.
+    pub fn resize(&mut self, screen_dimension: Dimension) -> component::Result<()> {
+        self.screen
+            .lock()?
+            .resize(screen_dimension.width, screen_dimension.height)?;
+        self.border
+            .set_boundary(layout::get_border_boundary(self.board()?))?;
+        self.render()
+    }
+
+    /// Move the cursor by one cell, it stay inside the map.
+    pub fn move_cursor(&mut self, direction: Direction) -> component::Result<()> {
+        let Dimension { width, height } = self.map.dimension();
+        let Position { x, y } = &mut self.cursor;
+        match direction {
+            Direction::Up => *y = y.saturating_sub(1),
+            Direction::Down => *y = (*y + 1).min(height.saturating_sub(1)),
+            Direction::Left => *x = x.saturating_sub(1),
+            Direction::Right => *x = (*x + 1).min(width.saturating_sub(1)),
+        }
+        self.render()
+    }
+
+    pub fn toggle_wall(&mut self) -> component::Result<()> {
+        self.map.toggle_wall(self.cursor);
+        self.changed()
+    }
+
+    pub fn toggle_apple_spawn(&mut self) -> component::Result<()> {
+        self.map.toggle_apple_spawn(self.cursor);
+        self.changed()
+    }
+
+    /// Start the snake under the cursor, or turn it clockwise when it already start there.
+    pub fn place_snake(&mut self) -> component::Result<()> {
+        let start = self.map.start();
+        let direction = if start.position() == self.cursor {
+            match start.direction() {
+                Direction::Up => Direction::Right,
+                Direction::Right => Direction::Down,
+                Direction::Down => Direction::Left,
+                Direction::Left => Direction::Up,
+            }
+        } else {
+            start.direction()
+        };
+        self.map.set_start(SnakeNode::new(self.cursor, direction));
+        self.changed()
+    }
+
+    /// Save the map to its file, the error is shown in the status bar when it cannot be saved.
+    pub fn save(&mut self) -> component::Result<()> {
+        match self.map.save(&self.path) {
+            Ok(()) => {
+                self.saved_map = Some(self.map.clone());
+                self.message = format!(" Saved to {} ", self.path.display());
+            }
+            Err(error) => self.message = format!(" {error} "),
+        }
+        self.render()
+    }
+
+    fn changed(&mut self) -> component::Result<()> {
+        self.message.clear();
+        self.render()
+    }
+
+    fn board(&self) -> component::Result<Boundary> {
+        let screen_dimension: Dimension = self.screen.lock()?.size().into();
+        Ok(Boundary::new(
+            layout::get_game_board_boundary(screen_dimension).position(),
+            self.map.dimension(),
+        ))
+    }
+}
@@ -0,0 +1,391 @@
+use std::{fmt, fs, path::Path, str::FromStr, sync::Arc};
+
+use accessors_rs::Accessors;
+use clap::ValueEnum;
+use error_chain::error_chain;
+
+use crate::{
+    component::{Boundary, Dimension, Position},
+    game_state::{fruit::FruitRates, snake::Direction, GameState, Player},
+    high_score,
+    map::Map,
+    settings::{value_name, Difficulty, GameMode, Opponent, SpeedCurve},
+};
+
+error_chain! {
+    errors {
+        UnsupportedVersion(header: String) {
+            description("Unsupported replay version.")
+            display("Unsupported replay version: '{}'", header)
+        }
+        InvalidLine(number: usize, line: String) {
+            description("Invalid line in the replay.")
+            display("Invalid line {} in the replay: '{}'", number, line)
+        }
+        MissingValue(name: &'static str) {
+            description("Missing value in the replay.")
+            display("The replay has no {}.", name)
+        }
+    }
+
+    links {
+        Map(crate::map::Error, crate::map::ErrorKind);
+    }
+
+    foreign_links {
+        Io(std::io::Error);
+    }
+}
+
+/// First line of every replay file, the number is incremented when the format change.
+const HEADER: &str = "snake-in-terminal replay";
+const VERSION: u32 = 1;
+
+/// Everything needed to play a round again.
+///
+/// The game state is deterministic, so the seed, the board and what changed between
+/// the steps of the game are enough to get the same round.
+#[derive(Debug, Clone, Accessors)]
+#[accessors(get_copy)]
+pub struct Replay {
+    seed: u64,
+    mode: GameMode,
+    opponent: Opponent,
+    #[accessors(get)]
+    map: Option<Arc<Map>>,
+    fruit_rates: FruitRates,
+    /// Dimension of the game board at the start of the round.
+    board: Dimension,
+    /// Speed of the round, with the level shown in the HUD.
+    difficulty: Difficulty,
+    speed_curve: SpeedCurve,
+    #[accessors(get)]
+    entries: Vec<ReplayEntry>,
+}
+
+/// Change to apply before the step of the game at the tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayEntry {
+    Turn(u64, Player, Direction),
+    Resize(u64, Dimension),
+}
+
+impl ReplayEntry {
+    pub fn tick(&self) -> u64 {
+        match self {
+            ReplayEntry::Turn(tick, ..) | ReplayEntry::Resize(tick, _) => *tick,
+        }
+    }
+}
+
+impl Replay {
+    pub fn new(
+        seed: u64,
+        mode: GameMode,
+        opponent: Opponent,
+        map: Option<Arc<Map>>,
+        fruit_rates: FruitRates,
+        board: Dimension,
+    ) -> Self {
+        Self {
+            seed,
+            mode,
+            opponent,
+            map,
+            fruit_rates,
+            board,
+            difficulty: Difficulty::default(),
+            speed_curve: SpeedCurve::default(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Replay played at the speed of the difficulty and of the speed curve.
+    pub fn with_speed(mut self, difficulty: Difficulty, speed_curve: SpeedCurve) -> Self {
+        self.difficulty = difficulty;
+        self.speed_curve = speed_curve;
+        self
+    }
+
+    /// Start the round of the replay with the game board at the position.
+    pub fn game_state(&self, position: Position) -> GameState {
+        GameState::new(
+            Boundary::new(position, self.board),
+            self.seed,
+            self.mode,
+            self.opponent,
+            self.map.clone(),
+            self.fruit_rates,
+        )
+    }
+
+    pub fn record_turn(&mut self, tick: u64, player: Player, direction: Direction) {
+        self.entries
+            .push(ReplayEntry::Turn(tick, player, direction));
+    }
+
+    pub fn record_resize(&mut self, tick: u64, board: Dimension) {
+        self.entries.push(ReplayEntry::Resize(tick, board));
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Write the replay without ever leaving it half written, even when the game crash.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(high_score::write_file(path.as_ref(), &self.to_string())?)
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER} {VERSION}")?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "mode {}", mode_name(self.mode))?;
+        writeln!(f, "opponent {}", opponent_name(self.opponent))?;
+        if let Some(map) = &self.map {
+            writeln!(f, "map {}", map.name())?;
+            for row in map.rows() {
+                writeln!(f, "row {row}")?;
+            }
+        }
+        let FruitRates {
+            golden,
+            shrink,
+            slow_motion,
+            poison,
+        } = self.fruit_rates;
+        writeln!(f, "fruits {golden} {shrink} {slow_motion} {poison}")?;
+        writeln!(f, "board {} {}", self.board.width, self.board.height)?;
+        writeln!(f, "difficulty {}", value_name(self.difficulty))?;
+        writeln!(f, "speed_curve {}", value_name(self.speed_curve))?;
+        for entry in &self.entries {
+            match entry {
+                ReplayEntry::Turn(tick, Player::One, direction) => {
+                    writeln!(f, "turn {tick} {}", direction.name())?
+                }
+                ReplayEntry::Turn(tick, Player::Two, direction) => {
+                    writeln!(f, "turn {tick} {} 2", direction.name())?
+                }
+                ReplayEntry::Resize(tick, board) => {
+                    writeln!(f, "resize {tick} {} {}", board.width, board.height)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Replay {
+    type Err = Error;
+
+    /// Parse a replay, the entries must be in the order of their tick.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut lines = s.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+
+        let header = lines.next().map(|(_, line)| line).unwrap_or_default();
+        if header != format!("{HEADER} {VERSION}") {
+            return Err(ErrorKind::UnsupportedVersion(header.to_string()).into());
+        }
+
+        let mut seed = None;
+        let mut mode = None;
+        let mut opponent = None;
+        let mut map_name = None;
+        let mut map_rows = Vec::new();
+        let mut fruit_rates = None;
+        let mut board = None;
+        let mut difficulty = None;
+        let mut speed_curve = None;
+        let mut entries: Vec<ReplayEntry> = Vec::new();
+        for (number, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let invalid_line = || ErrorKind::InvalidLine(number, line.to_string());
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["seed", value] => seed = Some(value.parse().map_err(|_| invalid_line())?),
+                ["mode", name] => mode = Some(parse_mode(name).ok_or_else(invalid_line)?),
+                ["opponent", name] => {
+                    opponent = Some(parse_opponent(name).ok_or_else(invalid_line)?)
+                }
+                ["map", ..] => map_name = line.strip_prefix("map").map(str::trim),
+                ["row", row] => map_rows.push((number, *row)),
+                ["fruits", golden, shrink, slow_motion, poison] => {
+                    fruit_rates = Some(
+                        parse_fruit_rates(golden, shrink, slow_motion, poison)
+                            .ok_or_else(invalid_line)?,
+                    )
+                }
+                ["board", width, height] => {
+                    board = Some(parse_dimension(width, height).ok_or_else(invalid_line)?)
+                }
+                ["difficulty", name] => {
+                    difficulty = Some(ValueEnum::from_str(name, false).map_err(|_| invalid_line())?)
+                }
+                ["speed_curve", name] => {
+                    speed_curve =
+                        Some(ValueEnum::from_str(name, false).map_err(|_| invalid_line())?)
+                }
+                ["turn", tick, direction] => entries.push(ReplayEntry::Turn(
+                    tick.parse().map_err(|_| invalid_line())?,
+                    Player::One,
+                    Direction::from_name(direction).ok_or_else(invalid_line)?,
+                )),
+                ["turn", tick, direction, "2"] => entries.push(ReplayEntry::Turn(
+                    tick.parse().map_err(|_| invalid_line())?,
+                    Player::Two,
+                    Direction::from_name(direction).ok_or_else(invalid_line)?,
+                )),
+                ["resize", tick, width, height] => entries.push(ReplayEntry::Resize(
+                    tick.parse().map_err(|_| invalid_line())?,
+                    parse_dimension(width, height).ok_or_else(invalid_line)?,
+                )),
+                _ => return Err(invalid_line().into()),
+            }
+            if let [.., previous, last] = entries.as_slice() {
+                if last.tick() < previous.tick() {
+                    return Err(invalid_line().into());
+                }
+            }
+        }
+
+        Ok(Self {
+            seed: seed.ok_or(ErrorKind::MissingValue("seed"))?,
+            mode: mode.ok_or(ErrorKind::MissingValue("mode"))?,
+            opponent: opponent.ok_or(ErrorKind::MissingValue("opponent"))?,
+            map: map_name
+                .map(|name| Map::from_rows(name, map_rows))
+                .transpose()?
+                .map(Arc::new),
+            fruit_rates: fruit_rates.ok_or(ErrorKind::MissingValue("fruit rates"))?,
+            board: board.ok_or(ErrorKind::MissingValue("board"))?,
+            difficulty: difficulty.ok_or(ErrorKind::MissingValue("difficulty"))?,
+            speed_curve: speed_curve.ok_or(ErrorKind::MissingValue("speed curve"))?,
+            entries,
+        })
+    }
+}
+
+pub fn mode_name(mode: GameMode) -> &'static str {
+    match mode {
+        GameMode::Classic => "classic",
+        GameMode::Wrap => "wrap",
+    }
+}
+
+fn parse_mode(name: &str) -> Option<GameMode> {
+    Some(match name {
+        "classic" => GameMode::Classic,
+        "wrap" => GameMode::Wrap,
+        _ => return None,
+    })
+}
+
+fn opponent_name(opponent: Opponent) -> &'static str {
+    match opponent {
+        Opponent::Solo => "solo",
+        Opponent::Player => "player",
+        Opponent::Computer => "computer",
+    }
+}
+
+fn parse_opponent(name: &str) -> Option<Opponent> {
+    Some(match name {
+        "solo" => Opponent::Solo,
+        "player" => Opponent::Player,
+        "computer" => Opponent::Computer,
+        _ => return None,
+    })
+}
+
+fn parse_dimension(width: &str, height: &str) -> Option<Dimension> {
+    Some(Dimension::new(width.parse().ok()?, height.parse().ok()?))
+}
+
+fn parse_fruit_rates(
+    golden: &str,
+    shrink: &str,
+    slow_motion: &str,
+    poison: &str,
+) -> Option<FruitRates> {
+    Some(FruitRates {
+        golden: golden.parse().ok()?,
+        shrink: shrink.parse().ok()?,
+        slow_motion: slow_motion.parse().ok()?,
+        poison: poison.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> Replay {
+        let mut replay = Replay::new(
+            42,
+            GameMode::Wrap,
+            Opponent::Computer,
+            Some(Arc::new(Map::new("empty room", Dimension::new(12, 6)))),
+            FruitRates {
+                golden: 5,
+                shrink: 0,
+                slow_motion: 10,
+                poison: 1,
+            },
+            Dimension::new(12, 6),
+        )
+        .with_speed(Difficulty::Hard, SpeedCurve::Score);
+        replay.record_turn(3, Player::One, Direction::Down);
+        replay.record_resize(5, Dimension::new(20, 10));
+        replay.record_turn(8, Player::Two, Direction::Up);
+        replay
+    }
+
+    #[test]
+    fn read_the_replay_written() {
+        let written = replay().to_string();
+        let read: Replay = written.parse().unwrap();
+        assert_eq!(read.to_string(), written);
+        assert_eq!(read.opponent(), Opponent::Computer);
+        assert_eq!(read.difficulty(), Difficulty::Hard);
+        assert_eq!(read.speed_curve(), SpeedCurve::Score);
+        assert_eq!(read.entries(), replay().entries());
+    }
+
+    #[test]
+    fn load_the_replay_saved() {
+        let path = std::env::temp_dir().join(format!("snake-replay-{}", std::process::id()));
+
+        replay().save(&path).unwrap();
+        let loaded = Replay::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap().to_string(), replay().to_string());
+    }
+
+    #[test]
+    fn refuse_another_version() {
+        let written = replay().to_string().replace(" replay 1\n", " replay 2\n");
+        assert!(matches!(
+            written.parse::<Replay>(),
+            Err(Error(ErrorKind::UnsupportedVersion(_), _))
+        ));
+    }
+
+    #[test]
+    fn refuse_a_replay_without_its_settings() {
+        for name in ["mode", "opponent", "difficulty", "speed curve"] {
+            let key = name.replace(' ', "_");
+            let written = replay()
+                .to_string()
+                .lines()
+                .filter(|line| !line.starts_with(&format!("{key} ")))
+                .map(|line| format!("{line}\n"))
+                .collect::<String>();
+            assert!(matches!(
+                written.parse::<Replay>(),
+                Err(Error(ErrorKind::MissingValue(missing), _)) if missing == name
+            ));
+        }
+    }
+}
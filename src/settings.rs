@@ -0,0 +1,252 @@
+use std::{sync::Arc, time::Duration};
+
+use clap::ValueEnum;
+use snake_in_terminal::terminus::style::ColorDepth;
+
+use crate::{
+    computer::Skill,
+    game_state::{fruit::FruitRates, snake::SNAKE_START_LENGTH, GameState},
+    map::Map,
+};
+
+/// Highest level a speed curve can reach.
+const MAX_LEVEL: u32 = 10;
+/// Each level divide the time between two steps by this much.
+const LEVEL_SPEED_UP: f64 = 0.88;
+/// Faster than this, the snake cannot be followed.
+const MIN_TICK_INTERVAL: Duration = Duration::from_millis(20);
+/// Nodes the snake must grow to reach the next level.
+const NODES_PER_LEVEL: usize = 5;
+/// Score to reach for each level after the first one.
+const SCORE_THRESHOLDS: [u32; MAX_LEVEL as usize - 1] =
+    [500, 1000, 2000, 3000, 4500, 6000, 8000, 10000, 12500];
+
+/// Rules of a round.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+pub enum GameMode {
+    /// The snake die when it hit a wall or itself.
+    #[default]
+    Classic,
+    /// The snake come back from the opposite wall, it only die when it hit itself.
+    Wrap,
+}
+
+impl GameMode {
+    const ALL: [GameMode; 2] = [GameMode::Classic, GameMode::Wrap];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classic",
+            GameMode::Wrap => "Wrap-around",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        cycle(&Self::ALL, self, 1)
+    }
+
+    pub fn previous(self) -> Self {
+        cycle(&Self::ALL, self, Self::ALL.len() - 1)
+    }
+}
+
+/// Who controls the second snake on the board.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+pub enum Opponent {
+    /// The snake is alone on the board.
+    #[default]
+    Solo,
+    /// A second player move the second snake with WASD.
+    Player,
+    /// The computer move the second snake toward the apples.
+    Computer,
+}
+
+impl Opponent {
+    const ALL: [Opponent; 3] = [Opponent::Solo, Opponent::Player, Opponent::Computer];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Opponent::Solo => "None",
+            Opponent::Player => "Player 2",
+            Opponent::Computer => "Computer",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        cycle(&Self::ALL, self, 1)
+    }
+
+    pub fn previous(self) -> Self {
+        cycle(&Self::ALL, self, Self::ALL.len() - 1)
+    }
+}
+
+/// Speed of the snake at the start of a round.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+impl Difficulty {
+    const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Insane,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Insane => "Insane",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        cycle(&Self::ALL, self, 1)
+    }
+
+    pub fn previous(self) -> Self {
+        cycle(&Self::ALL, self, Self::ALL.len() - 1)
+    }
+
+    /// Time between two steps of the game at the level, the first level is 1.
+    pub fn tick_interval(&self, level: u32) -> Duration {
+        let base = match self {
+            Difficulty::Easy => Duration::from_millis(110),
+            Difficulty::Normal => Duration::from_millis(75),
+            Difficulty::Hard => Duration::from_millis(50),
+            Difficulty::Insane => Duration::from_millis(30),
+        };
+        let level = level.clamp(1, MAX_LEVEL);
+        base.mul_f64(LEVEL_SPEED_UP.powi(level as i32 - 1))
+            .max(MIN_TICK_INTERVAL)
+    }
+}
+
+/// How the level, and so the speed of the snake, increase during a round.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+pub enum SpeedCurve {
+    /// The snake keep the speed of the difficulty.
+    #[default]
+    Constant,
+    /// One level every few nodes the snake grow.
+    Length,
+    /// One level each time the score cross a threshold.
+    Score,
+}
+
+impl SpeedCurve {
+    const ALL: [SpeedCurve; 3] = [SpeedCurve::Constant, SpeedCurve::Length, SpeedCurve::Score];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpeedCurve::Constant => "Constant",
+            SpeedCurve::Length => "By length",
+            SpeedCurve::Score => "By score",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        cycle(&Self::ALL, self, 1)
+    }
+
+    pub fn previous(self) -> Self {
+        cycle(&Self::ALL, self, Self::ALL.len() - 1)
+    }
+
+    /// Level reached in the round, starting at 1.
+    pub fn level(&self, state: &GameState) -> u32 {
+        let level = match self {
+            SpeedCurve::Constant => 1,
+            SpeedCurve::Length => {
+                let grown = state
+                    .snake()
+                    .nodes()
+                    .len()
+                    .saturating_sub(SNAKE_START_LENGTH as usize);
+                1 + (grown / NODES_PER_LEVEL) as u32
+            }
+            SpeedCurve::Score => {
+                let crossed = SCORE_THRESHOLDS
+                    .iter()
+                    .take_while(|threshold| state.score() >= **threshold)
+                    .count();
+                1 + crossed as u32
+            }
+        };
+        level.min(MAX_LEVEL)
+    }
+}
+
+/// Options chosen in the menus, used by the next rounds.
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub mode: GameMode,
+    pub opponent: Opponent,
+    /// How well the computer plays when it is the opponent.
+    pub computer_skill: Skill,
+    /// Level played, the board is empty when not set.
+    pub map: Option<Arc<Map>>,
+    pub difficulty: Difficulty,
+    pub speed_curve: SpeedCurve,
+    /// Chance that each fruit spawn when an apple is eaten.
+    pub fruit_rates: FruitRates,
+    /// Colors used to display the game, detected from the terminal when not set.
+    pub color_depth: Option<ColorDepth>,
+}
+
+impl Settings {
+    const COLOR_DEPTHS: [Option<ColorDepth>; 5] = [
+        None,
+        Some(ColorDepth::TrueColor),
+        Some(ColorDepth::Ansi256),
+        Some(ColorDepth::Ansi16),
+        Some(ColorDepth::Monochrome),
+    ];
+
+    pub fn color_depth_name(&self) -> &'static str {
+        match self.color_depth {
+            None => "Auto",
+            Some(ColorDepth::TrueColor) => "True color",
+            Some(ColorDepth::Ansi256) => "256 colors",
+            Some(ColorDepth::Ansi16) => "16 colors",
+            Some(ColorDepth::Monochrome) => "Monochrome",
+        }
+    }
+
+    /// Go to the next or the previous color depth and use it.
+    pub fn cycle_color_depth(&mut self, forward: bool) {
+        let step = if forward {
+            1
+        } else {
+            Self::COLOR_DEPTHS.len() - 1
+        };
+        self.color_depth = cycle(&Self::COLOR_DEPTHS, self.color_depth, step);
+        ColorDepth::set_current(self.color_depth.unwrap_or_else(ColorDepth::detect));
+    }
+}
+
+/// Name of the value on the command line, it does not change when the name shown in the menu does.
+pub fn value_name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// Value `step` places after the current one, going back to the start after the last.
+fn cycle<V: Copy + PartialEq>(values: &[V], current: V, step: usize) -> V {
+    let index = values
+        .iter()
+        .position(|v| *v == current)
+        .unwrap_or_default();
+    values[(index + step) % values.len()]
+}
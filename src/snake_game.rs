@@ -1,82 +1,439 @@
 use std::{
-    sync::{Arc, Weak},
+    io::Stdout,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
     time::Duration,
 };
 
 use accessors_rs::Accessors;
 
-use tokio::sync::Mutex;
+use snake_in_terminal::terminus::screen::SharedScreen;
+use tokio::{
+    sync::{mpsc::UnboundedSender, Mutex},
+    time::Instant,
+};
 
-use crate::component::{
-    self,
-    border::BorderComponent,
-    game_board::GameBoardComponent,
-    game_over::GameOverComponent,
-    timer::TimerComponent,
+use crate::{
+    bot::{self, BotRegistry},
+    component::{
+        self,
+        border::BorderComponent,
+        demo::DemoComponent,
+        game_board::GameBoardComponent,
+        game_over::{Banner, GameOverComponent},
+        pause::PauseComponent,
+        timer::TimerComponent,
+        Boundary, Dimension,
+    },
+    computer::Skill,
+    controller::{self, Controller, KeyboardController, ReplayController},
+    game_state::{snake::Direction, GameEvent, GameState, Player},
+    high_score::{HighScore, HighScores, RoundSettings},
+    layout,
+    replay::{Replay, ReplayEntry},
+    settings::{Difficulty, Opponent, SpeedCurve},
 };
 
 #[derive(Accessors)]
 pub struct SnakeGame {
-    _border: BorderComponent,
+    screen: SharedScreen<Stdout>,
+    border: BorderComponent,
     #[accessors(get)]
     timer: Arc<Mutex<TimerComponent>>,
     #[accessors(get, get_mut)]
     game_board: Arc<Mutex<GameBoardComponent>>,
     #[accessors(get, get_mut)]
     game_over: Arc<Mutex<GameOverComponent>>,
+    pause: PauseComponent,
+    is_paused: Arc<AtomicBool>,
+    /// Set when the game is left, the game loop end at its next tick.
+    is_stopped: Arc<AtomicBool>,
+    #[accessors(get)]
+    state: Arc<Mutex<GameState>>,
+    /// Where the directions typed by each player at the keyboard are sent during the round.
+    keyboards: Mutex<Vec<(Player, UnboundedSender<Direction>)>>,
+    /// Replay of the current round, created when the round start.
+    recording: Arc<Mutex<Option<Replay>>>,
+    /// File where the replay of a round is saved once the game is over.
+    #[accessors(set)]
+    record_path: Option<PathBuf>,
+    /// Replay played instead of the inputs of the player.
+    playback: Option<Arc<Replay>>,
+    /// File where the high scores are kept.
+    #[accessors(set)]
+    high_scores_path: Option<PathBuf>,
+    /// Speed of the snake at the first level.
+    #[accessors(set)]
+    difficulty: Difficulty,
+    /// How the level increase during the round.
+    #[accessors(set)]
+    speed_curve: SpeedCurve,
+    /// How well the computer plays when it is the opponent.
+    #[accessors(set)]
+    computer_skill: Skill,
+    /// Shown when the game plays itself, the rounds then follow each other without end.
+    #[accessors(set)]
+    demo: Option<DemoComponent>,
 }
 
 impl SnakeGame {
     pub fn new(
+        screen: SharedScreen<Stdout>,
+        state: Arc<Mutex<GameState>>,
         timer: Arc<Mutex<TimerComponent>>,
         game_board: Arc<Mutex<GameBoardComponent>>,
         game_over: Arc<Mutex<GameOverComponent>>,
+        pause: PauseComponent,
         border: BorderComponent,
     ) -> Self {
         Self {
-            _border: border,
+            screen,
+            border,
             timer,
             game_board,
             game_over,
+            pause,
+            is_paused: Arc::new(AtomicBool::new(false)),
+            is_stopped: Arc::new(AtomicBool::new(false)),
+            state,
+            keyboards: Mutex::new(Vec::new()),
+            recording: Arc::new(Mutex::new(None)),
+            record_path: None,
+            playback: None,
+            high_scores_path: None,
+            difficulty: Difficulty::default(),
+            speed_curve: SpeedCurve::default(),
+            computer_skill: Skill::default(),
+            demo: None,
+        }
+    }
+
+    /// Play the replay instead of the inputs of the player, must be called before the game loop start.
+    pub async fn set_playback(&mut self, replay: Replay) -> component::Result<()> {
+        let mut state = self.state.lock().await;
+        *state = replay.game_state(state.boundary().position());
+        self.border
+            .set_boundary(layout::get_border_boundary(state.boundary()))?;
+        // The round is played again at its own speed, whatever the settings of the menu.
+        self.difficulty = replay.difficulty();
+        self.speed_curve = replay.speed_curve();
+        self.playback = Some(Arc::new(replay));
+        Ok(())
+    }
+
+    /// Draw the game board from the current state.
+    ///
+    /// The border is drawn after the board, which erase where it was.
+    pub async fn render(&self) -> component::Result<()> {
+        let state = self.state.lock().await;
+        let level = self.speed_curve.level(&state);
+        self.game_board.lock().await.render(&state, level)?;
+        self.border.render()?;
+        if let Some(demo) = &self.demo {
+            demo.render()?;
         }
+        self.screen.lock()?.present()?;
+        Ok(())
+    }
+
+    /// Queue a direction change of the player for the next step.
+    ///
+    /// Ignored while the game is paused and when the snake of the player is not
+    /// moved with the keyboard.
+    pub async fn change_direction(&self, player: Player, direction: Direction) {
+        if self.is_paused() {
+            return;
+        }
+        let keyboards = self.keyboards.lock().await;
+        if let Some((_, keyboard)) = keyboards.iter().find(|(p, _)| *p == player) {
+            // The round may be over, then nobody listens anymore.
+            let _ = keyboard.send(direction);
+        }
+    }
+
+    /// What moves the snake of each player during the next round.
+    async fn controllers(&self, state: &GameState) -> Vec<(Player, Box<dyn Controller>)> {
+        let mut keyboards = Vec::new();
+        let bots = BotRegistry::new(self.computer_skill);
+        let bot = |name| {
+            bots.get(name)
+                .expect("The bots of the game are registered.")
+                .controller(state)
+        };
+        let controllers = state
+            .players()
+            .into_iter()
+            .map(|player| {
+                let controller: Box<dyn Controller> = match &self.playback {
+                    Some(replay) => Box::new(ReplayController::new(Arc::clone(replay))),
+                    None if self.demo.is_some() => bot(bot::CYCLE),
+                    None if player == Player::Two && state.opponent() == Opponent::Computer => {
+                        bot(bot::COMPUTER)
+                    }
+                    None => {
+                        let (keyboard, sender) = KeyboardController::new();
+                        keyboards.push((player, sender));
+                        Box::new(keyboard)
+                    }
+                };
+                (player, controller)
+            })
+            .collect();
+        *self.keyboards.lock().await = keyboards;
+        controllers
     }
 
     pub async fn start_game_loop(&self) {
         TimerComponent::start_timer(Arc::downgrade(&self.timer)).await;
+        {
+            let state = self.state.lock().await;
+            *self.recording.lock().await = Some(
+                Replay::new(
+                    state.seed(),
+                    state.mode(),
+                    state.opponent(),
+                    state.map().clone(),
+                    state.fruit_rates(),
+                    state.boundary().dimension(),
+                )
+                .with_speed(self.difficulty, self.speed_curve),
+            );
+        }
 
+        let recording = Arc::downgrade(&self.recording);
+        let record_path = self.record_path.clone();
+        let (is_solo, mut controllers) = {
+            let state = self.state.lock().await;
+            (
+                state.opponent() == Opponent::Solo,
+                self.controllers(&state).await,
+            )
+        };
+        // The rounds of a replay, of the demo and against an opponent are not high scores.
+        let is_demo = self.demo.is_some();
+        let high_scores_path = self
+            .high_scores_path
+            .clone()
+            .filter(|_| self.playback.is_none() && is_solo && !is_demo);
+        let playback = self.playback.clone();
+        let state = Arc::downgrade(self.state());
         let game_board = Arc::downgrade(self.game_board());
         let game_over = Arc::downgrade(self.game_over());
+        let timer = Arc::downgrade(self.timer());
+        let screen = SharedScreen::clone(&self.screen);
+        let is_paused = Arc::clone(&self.is_paused);
+        let is_stopped = Arc::clone(&self.is_stopped);
+        let difficulty = self.difficulty;
+        let speed_curve = self.speed_curve;
 
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_millis(75));
+            let mut level = 1;
+            let mut is_slow_motion = false;
+            let mut interval = tokio::time::interval(difficulty.tick_interval(level));
+            // Index of the next replay entry to play.
+            let mut next_entry = 0;
             loop {
                 interval.tick().await;
-                if let Some(game_board) = Weak::upgrade(&game_board) {
-                    let mut game_board = game_board.lock().await;
-                    // check is it's game over.
-                    if game_board.snake_component().snake().is_biting_itself()
-                        || !game_board
-                            .boundary()
-                            .is_inside(game_board.snake_component().snake().get_next_position())
-                    {
-                        if let Some(game_over) = game_over.upgrade() {
-                            game_over.lock().await.render()?;
-                            break;
+                let (Some(state), Some(recording), Some(game_board)) = (
+                    Weak::upgrade(&state),
+                    Weak::upgrade(&recording),
+                    Weak::upgrade(&game_board),
+                ) else {
+                    break;
+                };
+                let mut state = state.lock().await;
+                if is_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                if is_paused.load(Ordering::SeqCst) {
+                    continue;
+                }
+                // The turns of a replay are played by its controllers.
+                if let Some(replay) = &playback {
+                    let tick = state.tick();
+                    let entries = &replay.entries()[next_entry..];
+                    for entry in entries.iter().take_while(|e| e.tick() == tick) {
+                        if let ReplayEntry::Resize(_, board) = *entry {
+                            let position = state.boundary().position();
+                            state.set_boundary(Boundary::new(position, board));
                         }
+                        next_entry += 1;
                     }
-                    // check if the snake eat the apple.
-                    if game_board.apple().position()
-                        == game_board.snake_component().snake().head().position()
-                    {
-                        game_board.snake_component_mut().snake_mut().eat();
-                        game_board.score_mut().add(100)?;
-                        game_board.generate_new_apple()?;
+                }
+                // wait for the terminal to be big enough.
+                if !state.can_hold_snake() {
+                    continue;
+                }
+                let tick = state.tick();
+                let turns = controller::turns(&mut controllers, &state);
+                let events = state.step(&turns);
+                let mut recording = recording.lock().await;
+                if let Some(recording) = recording.as_mut() {
+                    for event in &events {
+                        if let GameEvent::DirectionChanged(player, direction) = event {
+                            recording.record_turn(tick, *player, *direction);
+                        }
                     }
-                    // move snake.
-                    game_board.snake_component_mut().move_forward()?;
                 }
+                let next_level = speed_curve.level(&state);
+                if next_level != level || state.is_slow_motion() != is_slow_motion {
+                    level = next_level;
+                    is_slow_motion = state.is_slow_motion();
+                    let period = state.tick_interval(difficulty.tick_interval(level));
+                    interval = tokio::time::interval_at(Instant::now() + period, period);
+                }
+                // The demo start a new round right away.
+                if events.contains(&GameEvent::GameOver) && is_demo {
+                    state.reset();
+                }
+                game_board.lock().await.render(&state, level)?;
+                if events.contains(&GameEvent::GameOver) && !is_demo {
+                    if let (Some(path), Some(recording)) = (&record_path, recording.as_ref()) {
+                        recording.save(path)?;
+                    }
+                    let mut elapsed = Duration::ZERO;
+                    if let Some(timer) = timer.upgrade() {
+                        let mut timer = timer.lock().await;
+                        timer.stop_timer();
+                        elapsed = timer.elapsed();
+                    }
+                    if let Some(game_over) = game_over.upgrade() {
+                        let mut game_over = game_over.lock().await;
+                        game_over.set_seed(state.seed());
+                        game_over.set_banner(Banner::of(&state));
+                        if let Some(path) = &high_scores_path {
+                            let high_score = HighScore::new(
+                                state.score(),
+                                state.snake().nodes().len(),
+                                elapsed,
+                                state.seed(),
+                                state.boundary().dimension(),
+                                RoundSettings::new(
+                                    state.mode(),
+                                    difficulty,
+                                    speed_curve,
+                                    state.map().as_deref(),
+                                    state.fruit_rates(),
+                                ),
+                            );
+                            // A file that cannot be read is left untouched.
+                            if let Ok(mut high_scores) = HighScores::load(path) {
+                                let rank = high_scores.insert(high_score.clone());
+                                let _ = high_scores.save(path);
+                                game_over.set_leaderboard(&high_scores, high_score, rank);
+                            }
+                        }
+                        game_over.render()?;
+                    }
+                    screen.lock()?.present()?;
+                    break;
+                }
+                screen.lock()?.present()?;
             }
             component::Result::Ok(())
         });
     }
+
+    /// Layout every component again for the new screen dimension.
+    ///
+    /// The game and the timer are paused while the game board is too small to hold the snake.
+    pub async fn resize(&mut self, screen_dimension: Dimension) -> component::Result<()> {
+        let mut state = self.state.lock().await;
+        let mut game_board = self.game_board.lock().await;
+        let mut game_over = self.game_over.lock().await;
+        let mut timer = self.timer.lock().await;
+
+        self.screen
+            .lock()?
+            .resize(screen_dimension.width, screen_dimension.height)?;
+        timer.set_position(layout::get_timer_position(screen_dimension))?;
+        // A replay keep the board it was recorded with.
+        if self.playback.is_none() {
+            state.set_boundary(layout::get_game_board_boundary(screen_dimension));
+            if let Some(recording) = self.recording.lock().await.as_mut() {
+                recording.record_resize(state.tick(), state.boundary().dimension());
+            }
+        }
+        game_board.render(&state, self.speed_curve.level(&state))?;
+        self.border
+            .set_boundary(layout::get_border_boundary(state.boundary()))?;
+        game_over.set_position(layout::get_game_over_position(screen_dimension))?;
+        self.pause
+            .set_position(layout::get_pause_position(screen_dimension))?;
+
+        if !state.can_hold_snake() {
+            timer.stop_timer();
+        } else if !timer.is_running() && !game_over.is_visible() && !self.pause.is_visible() {
+            drop(timer);
+            TimerComponent::start_timer(Arc::downgrade(&self.timer)).await;
+        }
+        self.screen.lock()?.present()?;
+        Ok(())
+    }
+
+    /// Start a new round once the game is over.
+    pub async fn restart(&mut self) -> component::Result<()> {
+        {
+            let mut state = self.state.lock().await;
+            let mut game_board = self.game_board.lock().await;
+            let mut game_over = self.game_over.lock().await;
+            let mut timer = self.timer.lock().await;
+            if !game_over.is_visible() {
+                return Ok(());
+            }
+
+            game_over.erase()?;
+            match &self.playback {
+                Some(replay) => *state = replay.game_state(state.boundary().position()),
+                None => state.reset(),
+            }
+            game_board.render(&state, self.speed_curve.level(&state))?;
+            timer.reset()?;
+            self.screen.lock()?.present()?;
+        }
+        self.start_game_loop().await;
+        Ok(())
+    }
+
+    /// End the game loop and the timer, nothing is drawn by the game after this.
+    pub async fn stop(&self) {
+        // The game loop hold the state while it draw, so it cannot be in the middle of a tick.
+        let _state = self.state.lock().await;
+        self.is_stopped.store(true, Ordering::SeqCst);
+        self.timer.lock().await.stop_timer();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.load(Ordering::SeqCst)
+    }
+
+    /// Freeze the game and the timer under an overlay, or resume them.
+    ///
+    /// Does nothing once the game is over.
+    pub async fn toggle_pause(&mut self) -> component::Result<()> {
+        let state = self.state.lock().await;
+        let game_over = self.game_over.lock().await;
+        let mut timer = self.timer.lock().await;
+        if game_over.is_visible() {
+            return Ok(());
+        }
+
+        if self.pause.is_visible() {
+            self.pause.erase()?;
+            self.is_paused.store(false, Ordering::SeqCst);
+            if state.can_hold_snake() {
+                drop(timer);
+                TimerComponent::start_timer(Arc::downgrade(&self.timer)).await;
+            }
+        } else {
+            self.is_paused.store(true, Ordering::SeqCst);
+            timer.stop_timer();
+            self.pause.render()?;
+        }
+        self.screen.lock()?.present()?;
+        Ok(())
+    }
 }
@@ -1,4 +1,8 @@
 pub mod ansi_escape_sequences;
+pub mod buffer;
 pub mod cursor;
+pub mod parser;
 pub mod screen;
+pub mod session;
 pub mod style;
+pub mod virtual_terminal;
@@ -2,7 +2,7 @@ use std::fmt::{Debug, Display, Formatter, Write};
 
 const ESCAPE: char = 0x1B_u8 as char;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ControlCharacters {
     /// Make bell sound
     Bell,
@@ -29,6 +29,7 @@ impl Display for ControlCharacters {
 }
 
 /// Control Sequence Introducer
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum CSI {
     /// Move the cursor up by n.
     ///
@@ -82,6 +83,8 @@ pub enum CSI {
     AuxPortOn,
     AuxPortOff,
     DeviceStatusReport,
+    /// Position n, m of the cursor sent by the terminal in response to a `DeviceStatusReport`.
+    CursorPositionReport(u16, u16),
     /// Save the current cursor position.
     SaveCursorPosition,
     /// Restore the saved cursor position.
@@ -90,6 +93,10 @@ pub enum CSI {
     RestoreCursorPosition,
     HideCursor,
     ShowCursor,
+    /// Switch to the alternative screen buffer, the main screen is kept untouched.
+    EnableAlternativeScreenBuffer,
+    /// Switch back to the main screen buffer.
+    DisableAlternativeScreenBuffer,
 }
 
 impl CSI {
@@ -104,18 +111,21 @@ impl CSI {
             | Self::CursorHorizontalAbsolute(n)
             | Self::ScrollUp(n)
             | Self::ScrollDown(n) => f.write_str(&n.to_string()),
-            Self::CursorPosition(n, m) | Self::HorizontalVerticalPosition(n, m) => {
-                f.write_str(&format!("{};{}", n, m))
-            }
+            Self::CursorPosition(n, m)
+            | Self::HorizontalVerticalPosition(n, m)
+            | Self::CursorPositionReport(n, m) => f.write_str(&format!("{};{}", n, m)),
             Self::EraseInDisplay(eo) | Self::EraseInLine(eo) => {
                 f.write_str((*eo as u8).to_string().as_str())
             }
-            Self::SelectGraphicRendition(srg) => srg.fmt(f),
+            Self::SelectGraphicRendition(srg) => Display::fmt(srg, f),
             Self::AuxPortOn => f.write_char('5'),
             Self::AuxPortOff => f.write_char('4'),
             Self::DeviceStatusReport => f.write_char('6'),
             CSI::SaveCursorPosition | CSI::RestoreCursorPosition => Ok(()),
             CSI::HideCursor | CSI::ShowCursor => f.write_str("?25"),
+            CSI::EnableAlternativeScreenBuffer | CSI::DisableAlternativeScreenBuffer => {
+                f.write_str("?1049")
+            }
         }
     }
 
@@ -137,10 +147,11 @@ impl CSI {
             Self::SelectGraphicRendition(_) => 'm',
             Self::AuxPortOn | Self::AuxPortOff => 'i',
             Self::DeviceStatusReport => 'n',
+            Self::CursorPositionReport(_, _) => 'R',
             CSI::SaveCursorPosition => 's',
             CSI::RestoreCursorPosition => 'u',
-            CSI::HideCursor => 'l',
-            CSI::ShowCursor => 'h',
+            CSI::HideCursor | CSI::DisableAlternativeScreenBuffer => 'l',
+            CSI::ShowCursor | CSI::EnableAlternativeScreenBuffer => 'h',
         })
     }
 }
@@ -164,6 +175,7 @@ impl Debug for CSI {
 }
 
 /// Select Graphic Rendition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SGR {
     /// Reset all Graphic Rendition.
     Reset,
@@ -202,6 +214,8 @@ pub enum SGR {
         g: u8,
         b: u8,
     },
+    /// Set the foreground to a color of the 256 colors palette.
+    SetForegroundColorIndexed(u8),
     DefaultForegroundColor,
     SetBackgroundColor(TerminalColor),
     SetBackgroundColorRGB {
@@ -209,6 +223,8 @@ pub enum SGR {
         g: u8,
         b: u8,
     },
+    /// Set the background to a color of the 256 colors palette.
+    SetBackgroundColorIndexed(u8),
     DefaultBackgroundColor,
     DisableProportionalSpacing,
     Framed,
@@ -254,6 +270,7 @@ impl Display for SGR {
             Self::SetForegroundColorRGB { r, g, b } => {
                 f.write_str(&format!("38;2;{};{};{}", r, g, b))
             }
+            Self::SetForegroundColorIndexed(index) => f.write_str(&format!("38;5;{}", index)),
             Self::DefaultForegroundColor => f.write_str("39"),
             Self::SetBackgroundColor(terminal_color) => {
                 f.write_str(&format!("4{}", *terminal_color as u8))
@@ -261,6 +278,7 @@ impl Display for SGR {
             Self::SetBackgroundColorRGB { r, g, b } => {
                 f.write_str(&format!("48;2;{};{};{}", r, g, b))
             }
+            Self::SetBackgroundColorIndexed(index) => f.write_str(&format!("48;5;{}", index)),
             Self::DefaultBackgroundColor => f.write_str("49"),
             Self::DisableProportionalSpacing => f.write_str("50"),
             Self::Framed => f.write_str("51"),
@@ -280,7 +298,7 @@ impl Display for SGR {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TerminalColor {
     Black = 0,
     Red = 1,
@@ -292,7 +310,7 @@ pub enum TerminalColor {
     White = 7,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EraseOption {
     CursorToEnd = 0,
     CursorToBeginning = 1,
@@ -0,0 +1,179 @@
+use crate::terminus::style::Style;
+
+/// A single character on the screen with the style used to render it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    symbol: char,
+    style: Style,
+}
+
+impl Cell {
+    pub fn new(symbol: char, style: Style) -> Self {
+        Self { symbol, style }
+    }
+
+    pub fn symbol(&self) -> char {
+        self.symbol
+    }
+
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(' ', Style::new())
+    }
+}
+
+/// Grid of styled cells that components draw into before the screen present it.
+///
+/// Positions are 1-based like `Cursor::move_to`, where 1, 1 is the top left corner.
+/// Everything drawn outside the buffer is clipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Buffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width as usize * height as usize],
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Resize the buffer and keep the cells that are still inside.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        let mut buffer = Self::new(width, height);
+        for y in 1..=height.min(self.height) {
+            for x in 1..=width.min(self.width) {
+                if let (Some(i), Some(cell)) = (buffer.index_of(x, y), self.get(x, y)) {
+                    buffer.cells[i] = cell.clone();
+                }
+            }
+        }
+        *self = buffer;
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::default());
+    }
+
+    /// Move every row up by n, the new rows at the bottom are empty.
+    pub fn scroll_up(&mut self, n: u16) {
+        let shift = (n.min(self.height) as usize) * self.width as usize;
+        self.cells.drain(..shift);
+        self.cells
+            .resize(self.width as usize * self.height as usize, Cell::default());
+    }
+
+    /// Move every row down by n, the new rows at the top are empty.
+    pub fn scroll_down(&mut self, n: u16) {
+        let shift = (n.min(self.height) as usize) * self.width as usize;
+        self.cells.truncate(self.cells.len() - shift);
+        self.cells
+            .splice(0..0, std::iter::repeat_n(Cell::default(), shift));
+    }
+
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn set_char(&mut self, x: u16, y: u16, c: char, style: &Style) {
+        if let Some(i) = self.index_of(x, y) {
+            let cell = &mut self.cells[i];
+            cell.symbol = c;
+            if &cell.style != style {
+                cell.style = Style::from(style);
+            }
+        }
+    }
+
+    /// Write the string from left to right starting at x, y.
+    pub fn set_str(&mut self, x: u16, y: u16, str: &str, style: &Style) {
+        for (i, c) in str.chars().enumerate() {
+            match x.checked_add(i as u16) {
+                Some(x) if x <= self.width => self.set_char(x, y, c, style),
+                _ => break,
+            }
+        }
+    }
+
+    /// Iterate over the cells that are different from the previous buffer.
+    ///
+    /// Cells outside the previous buffer are always considered different.
+    pub fn diff<'a>(&'a self, previous: &'a Buffer) -> impl Iterator<Item = (u16, u16, &'a Cell)> {
+        let width = self.width;
+        self.cells.iter().enumerate().filter_map(move |(i, cell)| {
+            let x = (i % width as usize) as u16 + 1;
+            let y = (i / width as usize) as u16 + 1;
+            if previous.get(x, y) == Some(cell) {
+                None
+            } else {
+                Some((x, y, cell))
+            }
+        })
+    }
+
+    fn index_of(&self, x: u16, y: u16) -> Option<usize> {
+        if x == 0 || y == 0 || x > self.width || y > self.height {
+            None
+        } else {
+            Some((y - 1) as usize * self.width as usize + (x - 1) as usize)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Buffer, Cell};
+    use crate::terminus::style::{Style, StyleProperty};
+
+    #[test]
+    fn find_no_difference_with_the_same_frame() {
+        let mut buffer = Buffer::new(6, 3);
+        buffer.set_str(1, 1, "snake", &Style::new());
+
+        assert_eq!(buffer.diff(&buffer.clone()).count(), 0);
+    }
+
+    #[test]
+    fn find_the_changed_cell() {
+        let previous = Buffer::new(6, 3);
+        let mut buffer = previous.clone();
+        let bold = Style::from([StyleProperty::Bold]);
+        buffer.set_char(4, 2, 'x', &bold);
+
+        let diff: Vec<(u16, u16, &Cell)> = buffer.diff(&previous).collect();
+
+        assert_eq!(diff, [(4, 2, &Cell::new('x', bold))]);
+    }
+
+    #[test]
+    fn find_a_style_change_on_the_same_glyph() {
+        let previous = Buffer::new(6, 3);
+        let mut buffer = previous.clone();
+        buffer.set_char(1, 1, ' ', &Style::from([StyleProperty::Underline]));
+
+        assert_eq!(buffer.diff(&previous).count(), 1);
+    }
+
+    #[test]
+    fn find_every_cell_outside_a_smaller_frame() {
+        let previous = Buffer::new(2, 2);
+        let buffer = Buffer::new(3, 2);
+
+        let diff: Vec<(u16, u16)> = buffer.diff(&previous).map(|(x, y, _)| (x, y)).collect();
+
+        assert_eq!(diff, [(3, 1), (3, 2)]);
+    }
+}
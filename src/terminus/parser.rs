@@ -0,0 +1,581 @@
+use crate::terminus::ansi_escape_sequences::{
+    ControlCharacters, EraseOption, TerminalColor, CSI, SGR,
+};
+
+const ESCAPE: u8 = 0x1B;
+const BELL: u8 = 0x07;
+/// Bytes kept for a sequence without its end, it is unknown once longer.
+const MAX_SEQUENCE_LENGTH: usize = 256;
+
+/// Value decoded from a stream of bytes written to a terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sequence {
+    /// Printable text.
+    Text(String),
+    Control(ControlCharacters),
+    /// A Select Graphic Rendition with many parameters is decoded as one `CSI` per `SGR`.
+    CSI(CSI),
+    /// Well formed sequence or byte that cannot be represented by the other variants.
+    Unknown(Vec<u8>),
+}
+
+/// Streaming decoder of ANSI escape sequences.
+///
+/// Bytes can be fed in chunks of any size, a sequence or a character split between two chunks
+/// is kept until the rest of it is received.
+#[derive(Debug, Default)]
+pub struct AnsiParser {
+    pending: Vec<u8>,
+}
+
+impl AnsiParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode the bytes, the incomplete sequence at the end is kept for the next call.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Sequence> {
+        let mut input = std::mem::take(&mut self.pending);
+        input.extend_from_slice(bytes);
+
+        let mut sequences = Vec::new();
+        let mut text = String::new();
+        let mut i = 0;
+        while i < input.len() {
+            let decoded = match input[i] {
+                ESCAPE => decode_escape(&input[i..]),
+                byte if byte < 0x20 || byte == 0x7F => Some((1, decode_control(byte))),
+                _ => match decode_char(&input[i..]) {
+                    Some((len, c)) => {
+                        text.push(c);
+                        i += len;
+                        continue;
+                    }
+                    None => None,
+                },
+            };
+            match decoded {
+                Some((len, decoded)) => {
+                    if !text.is_empty() {
+                        sequences.push(Sequence::Text(std::mem::take(&mut text)));
+                    }
+                    sequences.extend(decoded);
+                    i += len;
+                }
+                None => {
+                    self.pending = input[i..].to_vec();
+                    break;
+                }
+            }
+        }
+        if !text.is_empty() {
+            sequences.push(Sequence::Text(text));
+        }
+        sequences
+    }
+
+    /// True when the end of the last chunk was an incomplete sequence.
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Decode what is left when no more bytes are expected.
+    ///
+    /// A lone escape is the escape control character, anything else incomplete is unknown.
+    pub fn finish(&mut self) -> Vec<Sequence> {
+        match std::mem::take(&mut self.pending).as_slice() {
+            [] => vec![],
+            [ESCAPE] => vec![Sequence::Control(ControlCharacters::Escape)],
+            pending => vec![Sequence::Unknown(pending.to_vec())],
+        }
+    }
+}
+
+/// Decode the escape sequence at the start of bytes.
+///
+/// Return the number of bytes used, or `None` when the sequence is not complete.
+fn decode_escape(bytes: &[u8]) -> Option<(usize, Vec<Sequence>)> {
+    match *bytes.get(1)? {
+        b'[' => {
+            let mut end = 2;
+            loop {
+                if end >= MAX_SEQUENCE_LENGTH {
+                    return Some((end, vec![Sequence::Unknown(bytes[..end].to_vec())]));
+                }
+                match bytes.get(end)? {
+                    // Parameter and intermediate bytes.
+                    0x20..=0x3F => end += 1,
+                    // Final byte.
+                    0x40..=0x7E => break,
+                    // Malformed sequence, stop before the unexpected byte.
+                    _ => return Some((end, vec![Sequence::Unknown(bytes[..end].to_vec())])),
+                }
+            }
+            let parameters = String::from_utf8_lossy(&bytes[2..end]);
+            let sequences = decode_csi(&parameters, bytes[end] as char)
+                .unwrap_or_else(|| vec![Sequence::Unknown(bytes[..=end].to_vec())]);
+            Some((end + 1, sequences))
+        }
+        b']' => decode_osc(bytes),
+        ESCAPE => Some((1, vec![Sequence::Control(ControlCharacters::Escape)])),
+        _ => Some((2, vec![Sequence::Unknown(bytes[..2].to_vec())])),
+    }
+}
+
+/// Decode the operating system command at the start of bytes, it ends with a bell
+/// or a string terminator.
+///
+/// None of them is supported, so its text is unknown instead of printed.
+fn decode_osc(bytes: &[u8]) -> Option<(usize, Vec<Sequence>)> {
+    let mut end = 2;
+    let len = loop {
+        if end >= MAX_SEQUENCE_LENGTH {
+            break end;
+        }
+        match *bytes.get(end)? {
+            BELL => break end + 1,
+            ESCAPE => match *bytes.get(end + 1)? {
+                b'\\' => break end + 2,
+                // Another sequence start, the command is cut before it.
+                _ => break end,
+            },
+            _ => end += 1,
+        }
+    };
+    Some((len, vec![Sequence::Unknown(bytes[..len].to_vec())]))
+}
+
+fn decode_control(byte: u8) -> Vec<Sequence> {
+    vec![match byte {
+        BELL => Sequence::Control(ControlCharacters::Bell),
+        0x08 => Sequence::Control(ControlCharacters::Backspace),
+        0x09 => Sequence::Control(ControlCharacters::Tab),
+        0x0A => Sequence::Control(ControlCharacters::LineFeed),
+        0x0C => Sequence::Control(ControlCharacters::FormFeed),
+        0x0D => Sequence::Control(ControlCharacters::CarriageReturn),
+        _ => Sequence::Unknown(vec![byte]),
+    }]
+}
+
+/// Decode the UTF-8 character at the start of bytes.
+///
+/// Invalid bytes are decoded as the replacement character.
+fn decode_char(bytes: &[u8]) -> Option<(usize, char)> {
+    let len = match bytes[0] {
+        0xF0..=0xF7 => 4,
+        0xE0..=0xEF => 3,
+        0xC0..=0xDF => 2,
+        _ => 1,
+    };
+    if bytes.len() < len {
+        return None;
+    }
+    match std::str::from_utf8(&bytes[..len]) {
+        Ok(str) => str.chars().next().map(|c| (len, c)),
+        Err(_) => Some((1, char::REPLACEMENT_CHARACTER)),
+    }
+}
+
+fn decode_csi(parameters: &str, letter: char) -> Option<Vec<Sequence>> {
+    let (private, parameters) = match parameters.strip_prefix('?') {
+        Some(parameters) => (true, parameters),
+        None => (false, parameters),
+    };
+    let values = parameters
+        .split(';')
+        .map(|p| {
+            if p.is_empty() {
+                Ok(None)
+            } else {
+                p.parse().map(Some)
+            }
+        })
+        .collect::<std::result::Result<Vec<Option<u16>>, _>>()
+        .ok()?;
+    let n = values.first().copied().flatten();
+    let m = values.get(1).copied().flatten();
+    let count = n.unwrap_or(1);
+
+    let csi = match (private, letter) {
+        (true, 'l') if n == Some(25) => CSI::HideCursor,
+        (true, 'h') if n == Some(25) => CSI::ShowCursor,
+        (true, 'h') if n == Some(1049) => CSI::EnableAlternativeScreenBuffer,
+        (true, 'l') if n == Some(1049) => CSI::DisableAlternativeScreenBuffer,
+        (true, _) => return None,
+        (false, 'A') => CSI::CursorUp(count),
+        (false, 'B') => CSI::CursorDown(count),
+        (false, 'C') => CSI::CursorForward(count),
+        (false, 'D') => CSI::CursorBack(count),
+        (false, 'E') => CSI::CursorNextLine(count),
+        (false, 'F') => CSI::CursorPreviousLine(count),
+        (false, 'G') => CSI::CursorHorizontalAbsolute(count),
+        (false, 'H') => CSI::CursorPosition(count, m.unwrap_or(1)),
+        (false, 'f') => CSI::HorizontalVerticalPosition(count, m.unwrap_or(1)),
+        (false, 'J') => CSI::EraseInDisplay(erase_option(n.unwrap_or(0))?),
+        (false, 'K') => CSI::EraseInLine(erase_option(n.unwrap_or(0))?),
+        (false, 'S') => CSI::ScrollUp(count),
+        (false, 'T') => CSI::ScrollDown(count),
+        (false, 'i') if n == Some(5) => CSI::AuxPortOn,
+        (false, 'i') if n == Some(4) => CSI::AuxPortOff,
+        (false, 'n') if n == Some(6) => CSI::DeviceStatusReport,
+        (false, 'R') => CSI::CursorPositionReport(count, m.unwrap_or(1)),
+        (false, 's') => CSI::SaveCursorPosition,
+        (false, 'u') => CSI::RestoreCursorPosition,
+        (false, 'm') => {
+            let values: Vec<u16> = values.into_iter().map(|v| v.unwrap_or(0)).collect();
+            return Some(
+                decode_sgr(&values)?
+                    .into_iter()
+                    .map(|sgr| Sequence::CSI(CSI::SelectGraphicRendition(sgr)))
+                    .collect(),
+            );
+        }
+        _ => return None,
+    };
+    Some(vec![Sequence::CSI(csi)])
+}
+
+/// Decode the parameters of a Select Graphic Rendition, an empty list is a reset.
+fn decode_sgr(values: &[u16]) -> Option<Vec<SGR>> {
+    let mut sgrs = Vec::new();
+    let mut values = values.iter().copied();
+    while let Some(value) = values.next() {
+        sgrs.push(match value {
+            0 => SGR::Reset,
+            1 => SGR::Bold,
+            2 => SGR::Dim,
+            3 => SGR::Italic,
+            4 => SGR::Underline,
+            5 => SGR::SlowBlink,
+            6 => SGR::RapidBlink,
+            7 => SGR::Invert,
+            8 => SGR::Hide,
+            9 => SGR::Strike,
+            10 => SGR::DefaultFont,
+            11..=19 => SGR::SetAlternativeFont((value - 10) as u8),
+            20 => SGR::Fraktur,
+            21 => SGR::DoublyUnderlined,
+            22 => SGR::NormalIntensity,
+            23 => SGR::NeitherItalicNorBlackletter,
+            24 => SGR::NotUnderlined,
+            25 => SGR::NotBlinking,
+            26 => SGR::ProportionalSpacing,
+            27 => SGR::NotReversed,
+            28 => SGR::Reveal,
+            29 => SGR::NotCrossedOut,
+            30..=37 => SGR::SetForegroundColor(terminal_color(value - 30)?),
+            38 => match extended_color(&mut values)? {
+                ExtendedColor::Indexed(index) => SGR::SetForegroundColorIndexed(index),
+                ExtendedColor::Rgb(r, g, b) => SGR::SetForegroundColorRGB { r, g, b },
+            },
+            39 => SGR::DefaultForegroundColor,
+            40..=47 => SGR::SetBackgroundColor(terminal_color(value - 40)?),
+            48 => match extended_color(&mut values)? {
+                ExtendedColor::Indexed(index) => SGR::SetBackgroundColorIndexed(index),
+                ExtendedColor::Rgb(r, g, b) => SGR::SetBackgroundColorRGB { r, g, b },
+            },
+            49 => SGR::DefaultBackgroundColor,
+            50 => SGR::DisableProportionalSpacing,
+            51 => SGR::Framed,
+            52 => SGR::Encircled,
+            53 => SGR::Overlined,
+            54 => SGR::NeitherFramedNorEncircled,
+            55 => SGR::NotOverlined,
+            58 => SGR::SetUnderlineColor,
+            59 => SGR::DefaultUnderlineColor,
+            90..=97 => SGR::SetBrightForegroundColor(terminal_color(value - 90)?),
+            100..=107 => SGR::SetBrightBackgroundColor(terminal_color(value - 100)?),
+            _ => return None,
+        });
+    }
+    if sgrs.is_empty() {
+        sgrs.push(SGR::Reset);
+    }
+    Some(sgrs)
+}
+
+enum ExtendedColor {
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// Read the `5;n` or `2;r;g;b` parameters of an extended color.
+fn extended_color(values: &mut impl Iterator<Item = u16>) -> Option<ExtendedColor> {
+    let mut next = || u8::try_from(values.next()?).ok();
+    match next()? {
+        5 => Some(ExtendedColor::Indexed(next()?)),
+        2 => Some(ExtendedColor::Rgb(next()?, next()?, next()?)),
+        _ => None,
+    }
+}
+
+fn terminal_color(index: u16) -> Option<TerminalColor> {
+    Some(match index {
+        0 => TerminalColor::Black,
+        1 => TerminalColor::Red,
+        2 => TerminalColor::Green,
+        3 => TerminalColor::Yellow,
+        4 => TerminalColor::Blue,
+        5 => TerminalColor::Magenta,
+        6 => TerminalColor::Cyan,
+        7 => TerminalColor::White,
+        _ => return None,
+    })
+}
+
+fn erase_option(value: u16) -> Option<EraseOption> {
+    Some(match value {
+        0 => EraseOption::CursorToEnd,
+        1 => EraseOption::CursorToBeginning,
+        2 => EraseOption::All,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnsiParser, Sequence, MAX_SEQUENCE_LENGTH};
+    use crate::terminus::ansi_escape_sequences::{
+        ControlCharacters, EraseOption, TerminalColor, CSI, SGR,
+    };
+
+    const COLORS: [TerminalColor; 8] = [
+        TerminalColor::Black,
+        TerminalColor::Red,
+        TerminalColor::Green,
+        TerminalColor::Yellow,
+        TerminalColor::Blue,
+        TerminalColor::Magenta,
+        TerminalColor::Cyan,
+        TerminalColor::White,
+    ];
+
+    /// Parse what the sequence display, it must give the sequence back.
+    fn assert_round_trip(csi: CSI) {
+        let displayed = csi.to_string();
+        let mut parser = AnsiParser::new();
+        assert_eq!(
+            parser.feed(displayed.as_bytes()),
+            vec![Sequence::CSI(csi)],
+            "{displayed:?}"
+        );
+        assert!(!parser.has_pending());
+    }
+
+    #[test]
+    fn round_trip_cursor_moves() {
+        for n in [0, 1, 2, 9, 10, 255, 1000, u16::MAX] {
+            assert_round_trip(CSI::CursorUp(n));
+            assert_round_trip(CSI::CursorDown(n));
+            assert_round_trip(CSI::CursorForward(n));
+            assert_round_trip(CSI::CursorBack(n));
+            assert_round_trip(CSI::CursorNextLine(n));
+            assert_round_trip(CSI::CursorPreviousLine(n));
+            assert_round_trip(CSI::CursorHorizontalAbsolute(n));
+            assert_round_trip(CSI::ScrollUp(n));
+            assert_round_trip(CSI::ScrollDown(n));
+            for m in [1, 80, u16::MAX] {
+                assert_round_trip(CSI::CursorPosition(n, m));
+                assert_round_trip(CSI::HorizontalVerticalPosition(n, m));
+                assert_round_trip(CSI::CursorPositionReport(n, m));
+            }
+        }
+        assert_round_trip(CSI::SaveCursorPosition);
+        assert_round_trip(CSI::RestoreCursorPosition);
+        assert_round_trip(CSI::HideCursor);
+        assert_round_trip(CSI::ShowCursor);
+        assert_round_trip(CSI::EnableAlternativeScreenBuffer);
+        assert_round_trip(CSI::DisableAlternativeScreenBuffer);
+        assert_round_trip(CSI::DeviceStatusReport);
+    }
+
+    #[test]
+    fn round_trip_erase() {
+        for option in [
+            EraseOption::CursorToEnd,
+            EraseOption::CursorToBeginning,
+            EraseOption::All,
+        ] {
+            assert_round_trip(CSI::EraseInDisplay(option));
+            assert_round_trip(CSI::EraseInLine(option));
+        }
+    }
+
+    #[test]
+    fn round_trip_graphic_renditions() {
+        let mut sgrs = vec![
+            SGR::Reset,
+            SGR::Bold,
+            SGR::Dim,
+            SGR::Italic,
+            SGR::Underline,
+            SGR::SlowBlink,
+            SGR::RapidBlink,
+            SGR::Invert,
+            SGR::Hide,
+            SGR::Strike,
+            SGR::DefaultFont,
+            SGR::Fraktur,
+            SGR::DoublyUnderlined,
+            SGR::NormalIntensity,
+            SGR::NeitherItalicNorBlackletter,
+            SGR::NotUnderlined,
+            SGR::NotBlinking,
+            SGR::ProportionalSpacing,
+            SGR::NotReversed,
+            SGR::Reveal,
+            SGR::NotCrossedOut,
+            SGR::DefaultForegroundColor,
+            SGR::DefaultBackgroundColor,
+            SGR::DisableProportionalSpacing,
+            SGR::Framed,
+            SGR::Encircled,
+            SGR::Overlined,
+            SGR::NeitherFramedNorEncircled,
+            SGR::NotOverlined,
+            SGR::SetUnderlineColor,
+            SGR::DefaultUnderlineColor,
+        ];
+        sgrs.extend((1..=9).map(SGR::SetAlternativeFont));
+        for color in COLORS {
+            sgrs.push(SGR::SetForegroundColor(color));
+            sgrs.push(SGR::SetBackgroundColor(color));
+            sgrs.push(SGR::SetBrightForegroundColor(color));
+            sgrs.push(SGR::SetBrightBackgroundColor(color));
+        }
+        for sgr in sgrs {
+            assert_round_trip(CSI::SelectGraphicRendition(sgr));
+        }
+    }
+
+    #[test]
+    fn round_trip_256_colors() {
+        for index in 0..=u8::MAX {
+            assert_round_trip(CSI::SelectGraphicRendition(SGR::SetForegroundColorIndexed(
+                index,
+            )));
+            assert_round_trip(CSI::SelectGraphicRendition(SGR::SetBackgroundColorIndexed(
+                index,
+            )));
+        }
+    }
+
+    #[test]
+    fn round_trip_rgb_colors() {
+        for (r, g, b) in [(0, 0, 0), (255, 255, 255), (235, 35, 55), (1, 20, 200)] {
+            assert_round_trip(CSI::SelectGraphicRendition(SGR::SetForegroundColorRGB {
+                r,
+                g,
+                b,
+            }));
+            assert_round_trip(CSI::SelectGraphicRendition(SGR::SetBackgroundColorRGB {
+                r,
+                g,
+                b,
+            }));
+        }
+    }
+
+    #[test]
+    fn round_trip_control_characters() {
+        for control in [
+            ControlCharacters::Bell,
+            ControlCharacters::Backspace,
+            ControlCharacters::Tab,
+            ControlCharacters::LineFeed,
+            ControlCharacters::FormFeed,
+            ControlCharacters::CarriageReturn,
+        ] {
+            let mut parser = AnsiParser::new();
+            assert_eq!(
+                parser.feed(control.to_string().as_bytes()),
+                vec![Sequence::Control(control)]
+            );
+        }
+    }
+
+    #[test]
+    fn keep_a_sequence_split_between_two_chunks() {
+        let displayed = format!(
+            "a{}é",
+            CSI::SelectGraphicRendition(SGR::SetForegroundColorRGB { r: 1, g: 2, b: 3 })
+        );
+        let bytes = displayed.as_bytes();
+        for split in 0..=bytes.len() {
+            let mut parser = AnsiParser::new();
+            let mut sequences = parser.feed(&bytes[..split]);
+            sequences.extend(parser.feed(&bytes[split..]));
+            let text: String = sequences
+                .iter()
+                .filter_map(|sequence| match sequence {
+                    Sequence::Text(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(text, "aé");
+            assert!(
+                sequences.contains(&Sequence::CSI(CSI::SelectGraphicRendition(
+                    SGR::SetForegroundColorRGB { r: 1, g: 2, b: 3 }
+                )))
+            );
+        }
+    }
+
+    #[test]
+    fn skip_operating_system_commands() {
+        for terminator in ["\x07", "\x1B\\"] {
+            let osc = format!("\x1B]0;window title{terminator}");
+            let bytes = format!("a{osc}b");
+            for split in 0..=bytes.len() {
+                let mut parser = AnsiParser::new();
+                let mut sequences = parser.feed(&bytes.as_bytes()[..split]);
+                sequences.extend(parser.feed(&bytes.as_bytes()[split..]));
+                let text: String = sequences
+                    .iter()
+                    .filter_map(|sequence| match sequence {
+                        Sequence::Text(text) => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                assert_eq!(text, "ab", "{split}");
+                assert!(sequences.contains(&Sequence::Unknown(osc.as_bytes().to_vec())));
+                assert!(!parser.has_pending());
+            }
+        }
+    }
+
+    #[test]
+    fn cut_an_operating_system_command_before_another_sequence() {
+        let mut parser = AnsiParser::new();
+        assert_eq!(
+            parser.feed(b"\x1B]2;title\x1B[2J"),
+            vec![
+                Sequence::Unknown(b"\x1B]2;title".to_vec()),
+                Sequence::CSI(CSI::EraseInDisplay(EraseOption::All)),
+            ]
+        );
+    }
+
+    #[test]
+    fn give_up_on_a_sequence_without_end() {
+        let mut parser = AnsiParser::new();
+        let parameters = "1;".repeat(MAX_SEQUENCE_LENGTH);
+        let mut sequences = Vec::new();
+        for chunk in format!("\x1B[{parameters}").as_bytes().chunks(7) {
+            sequences.extend(parser.feed(chunk));
+            assert!(parser.pending.len() < MAX_SEQUENCE_LENGTH);
+        }
+        assert!(matches!(
+            &sequences[0],
+            Sequence::Unknown(bytes) if bytes.len() == MAX_SEQUENCE_LENGTH
+        ));
+
+        let mut parser = AnsiParser::new();
+        let title = "a".repeat(MAX_SEQUENCE_LENGTH);
+        let sequences = parser.feed(format!("\x1B]0;{title}").as_bytes());
+        assert!(
+            matches!(&sequences[0], Sequence::Unknown(bytes) if bytes.len() == MAX_SEQUENCE_LENGTH)
+        );
+        assert!(!parser.has_pending());
+    }
+}
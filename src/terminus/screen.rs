@@ -1,6 +1,6 @@
 use derive_deref_rs::Deref;
 use std::{
-    io::{self, Write},
+    io::Write,
     sync::{Arc, Mutex, MutexGuard},
 };
 
@@ -9,7 +9,9 @@ use terminal_size::{Height, Width};
 
 use crate::terminus::{
     ansi_escape_sequences::{EraseOption, CSI},
+    buffer::Buffer,
     cursor::Cursor,
+    style::{ColorDepth, Style},
 };
 
 error_chain! {
@@ -28,20 +30,105 @@ error_chain! {
 pub struct Screen<T: Write + Send> {
     out: T,
     cursor: Cursor<T>,
+    /// Frame being drawn by the components.
+    back_buffer: Buffer,
+    /// Frame currently displayed in the terminal.
+    front_buffer: Buffer,
+    /// Color depth of the output, the current one when not set.
+    color_depth: Option<ColorDepth>,
 }
 
 impl<T: Write + Send> Screen<T> {
     pub fn new(op: impl Fn() -> T) -> Screen<T> {
+        let (width, height) = terminal_size::terminal_size()
+            .map(|(Width(w), Height(h))| (w, h))
+            .unwrap_or((0, 0));
+        Self::with_size(op, width, height)
+    }
+
+    /// Create a screen with a fixed size instead of the terminal size.
+    pub fn with_size(op: impl Fn() -> T, width: u16, height: u16) -> Screen<T> {
         Self {
             out: op(),
             cursor: Cursor::from(op),
+            back_buffer: Buffer::new(width, height),
+            front_buffer: Buffer::new(width, height),
+            color_depth: None,
         }
     }
 
+    /// Display the colors with this depth, whatever the depth of the other screens.
+    pub fn set_color_depth(&mut self, depth: ColorDepth) {
+        self.color_depth = Some(depth);
+    }
+
     pub fn cursor_mut(&mut self) -> &mut Cursor<T> {
         &mut self.cursor
     }
 
+    pub fn buffer(&self) -> &Buffer {
+        &self.back_buffer
+    }
+
+    pub fn buffer_mut(&mut self) -> &mut Buffer {
+        &mut self.back_buffer
+    }
+
+    /// Draw a string in the back buffer, it will be visible at the next `present`.
+    pub fn draw_str(&mut self, x: u16, y: u16, str: &str, style: &Style) {
+        self.back_buffer.set_str(x, y, str, style);
+    }
+
+    /// Draw a character in the back buffer, it will be visible at the next `present`.
+    pub fn draw_char(&mut self, x: u16, y: u16, c: char, style: &Style) {
+        self.back_buffer.set_char(x, y, c, style);
+    }
+
+    /// Output the difference between the back buffer and the frame displayed in the terminal.
+    ///
+    /// Only the changed cells are written, with the shortest cursor move to reach them
+    /// and a Graphic Rendition change only when the style differ from the previous cell.
+    /// The cursor is put back where it was once the cells are written.
+    pub fn present(&mut self) -> std::io::Result<()> {
+        let mut output = CSI::SaveCursorPosition.to_string();
+        let mut cursor: Option<(u16, u16)> = None;
+        let mut style: Option<&Style> = None;
+        let depth = self.color_depth.unwrap_or_else(ColorDepth::current);
+
+        for (x, y, cell) in self.back_buffer.diff(&self.front_buffer) {
+            match cursor {
+                Some(position) if position == (x, y) => {}
+                Some((cursor_x, cursor_y)) if cursor_y == y && cursor_x < x => {
+                    let forward = CSI::CursorForward(x - cursor_x).to_string();
+                    let position = CSI::CursorPosition(y, x).to_string();
+                    output += if forward.len() < position.len() {
+                        forward
+                    } else {
+                        position
+                    }
+                    .as_str();
+                }
+                _ => output += CSI::CursorPosition(y, x).to_string().as_str(),
+            }
+            if style != Some(cell.style()) {
+                output += Style::RESET;
+                output += cell.style().ansi_sequence_with(depth).as_str();
+                style = Some(cell.style());
+            }
+            output.push(cell.symbol());
+            cursor = Some((x + 1, y));
+        }
+
+        if cursor.is_some() {
+            output += Style::RESET;
+            output += CSI::RestoreCursorPosition.to_string().as_str();
+            self.out.write_all(output.as_bytes())?;
+            self.out.flush()?;
+        }
+        self.front_buffer.clone_from(&self.back_buffer);
+        Ok(())
+    }
+
     pub fn try_size(&self) -> Result<(u16, u16)> {
         if let Some((Width(w), Height(h))) = terminal_size::terminal_size() {
             Ok((w, h))
@@ -51,30 +138,45 @@ impl<T: Write + Send> Screen<T> {
     }
 
     pub fn size(&self) -> (u16, u16) {
-        self.try_size().unwrap_or((0, 0).into())
+        self.try_size().unwrap_or((0, 0))
     }
 
+    /// Resize both buffers and clear the terminal.
+    ///
+    /// A terminal reflow its content when resized, so everything need to be drawn again.
+    pub fn resize(&mut self, width: u16, height: u16) -> std::io::Result<()> {
+        self.back_buffer = Buffer::new(width, height);
+        self.front_buffer = Buffer::new(width, height);
+        self.clear_screen()
+    }
+
+    /// Clear the terminal and both buffers.
     pub fn clear_screen(&mut self) -> std::io::Result<()> {
         self.out
-            .write(CSI::EraseInDisplay(EraseOption::All).to_string().as_bytes())?;
+            .write_all(CSI::EraseInDisplay(EraseOption::All).to_string().as_bytes())?;
         self.cursor.move_to(1, 1)?;
+        self.back_buffer.clear();
+        self.front_buffer.clear();
         self.out.flush()
     }
 
+    /// Erase the terminal and both buffers without keeping the previous print in the history.
     pub fn erase_screen(&mut self) -> std::io::Result<()> {
         self.cursor.move_to(1, 1)?;
-        self.out.write(
+        self.out.write_all(
             CSI::EraseInDisplay(EraseOption::CursorToEnd)
                 .to_string()
                 .as_bytes(),
         )?;
+        self.back_buffer.clear();
+        self.front_buffer.clear();
         self.out.flush()
     }
 
     pub fn scroll_to_bottom(&mut self) -> Result<()> {
         let height = self.try_size()?.0;
         self.out
-            .write(CSI::ScrollDown(height).to_string().as_bytes())?;
+            .write_all(CSI::ScrollDown(height).to_string().as_bytes())?;
         self.cursor.move_to(1, height)?;
         self.out.flush()?;
         Ok(())
@@ -82,41 +184,42 @@ impl<T: Write + Send> Screen<T> {
 
     /// Scroll Content down without bringing history in the screen.
     pub fn scroll_down(&mut self, n: u16) -> Result<()> {
-        self.out.write(CSI::ScrollDown(n).to_string().as_bytes())?;
+        self.out
+            .write_all(CSI::ScrollDown(n).to_string().as_bytes())?;
         self.out.flush()?;
         Ok(())
     }
 
     /// Scroll Content up but doest not write if higher than the screen.
     pub fn scroll_up(&mut self, n: u16) -> Result<()> {
-        self.out.write(CSI::ScrollUp(n).to_string().as_bytes())?;
+        self.out
+            .write_all(CSI::ScrollUp(n).to_string().as_bytes())?;
         self.out.flush()?;
         Ok(())
     }
 
     pub fn writeln(&mut self) -> Result<()> {
-        self.out.write(&['\n' as u8])?;
+        self.out.write_all(b"\n")?;
         self.out.flush()?;
         Ok(())
     }
 
     pub fn write_str(&mut self, str: &str) -> Result<()> {
-        self.out.write(str.as_bytes())?;
+        self.out.write_all(str.as_bytes())?;
         self.out.flush()?;
         Ok(())
     }
 
     pub fn writeln_str(&mut self, str: &str) -> Result<()> {
-        self.out.write(str.as_bytes())?;
-        self.out.write(&['\n' as u8])?;
+        self.out.write_all(str.as_bytes())?;
+        self.out.write_all(b"\n")?;
         self.out.flush()?;
         Ok(())
     }
 
     pub fn write_char(&mut self, c: char) -> Result<()> {
         let mut buf: [u8; 4] = [0, 0, 0, 0];
-        c.encode_utf8(&mut buf);
-        self.out.write(&buf)?;
+        self.out.write_all(c.encode_utf8(&mut buf).as_bytes())?;
         self.out.flush()?;
         Ok(())
     }
@@ -138,8 +241,7 @@ impl<T: Write + Send> SharedScreen<T> {
             .screen
             .lock()
             .map_err(|_| Error::from_kind(ErrorKind::CannotLockSharedScreen))?;
-        let screen_guard = SharedScreenGuard::new(screen)?;
-        Ok(screen_guard)
+        Ok(SharedScreenGuard::new(screen))
     }
 }
 
@@ -157,17 +259,88 @@ pub struct SharedScreenGuard<'a, T: Write + Send> {
 }
 
 impl<'a, T: Write + Send> SharedScreenGuard<'a, T> {
-    pub fn new(mutex: MutexGuard<'a, Screen<T>>) -> io::Result<Self> {
-        let mut ssg = Self {
+    pub fn new(mutex: MutexGuard<'a, Screen<T>>) -> Self {
+        Self {
             screen_guard: mutex,
-        };
-        ssg.cursor_mut().save_position()?;
-        Ok(ssg)
+        }
     }
 }
 
-impl<'a, T: Write + Send> Drop for SharedScreenGuard<'a, T> {
-    fn drop(&mut self) {
-        let _ = self.cursor_mut().restore_position();
+#[cfg(test)]
+mod tests {
+    use super::Screen;
+    use crate::terminus::{
+        ansi_escape_sequences::CSI,
+        style::{Style, StyleProperty},
+    };
+
+    fn screen() -> Screen<Vec<u8>> {
+        Screen::with_size(Vec::new, 10, 4)
+    }
+
+    fn output(screen: &mut Screen<Vec<u8>>) -> String {
+        String::from_utf8(std::mem::take(&mut screen.out)).unwrap()
+    }
+
+    #[test]
+    fn write_nothing_for_an_unchanged_frame() {
+        let mut screen = screen();
+        screen.present().unwrap();
+        assert_eq!(output(&mut screen), "");
+
+        let bold = Style::from([StyleProperty::Bold]);
+        screen.draw_str(2, 2, "snake", &bold);
+        screen.present().unwrap();
+        output(&mut screen);
+        screen.draw_str(2, 2, "snake", &bold);
+        screen.present().unwrap();
+        assert_eq!(output(&mut screen), "");
+    }
+
+    #[test]
+    fn write_one_move_and_the_glyph_of_a_changed_cell() {
+        let mut screen = screen();
+        let bold = Style::from([StyleProperty::Bold]);
+
+        screen.draw_char(7, 3, 'o', &bold);
+        screen.present().unwrap();
+
+        assert_eq!(
+            output(&mut screen),
+            format!(
+                "{}{}{}{}o{}{}",
+                CSI::SaveCursorPosition,
+                CSI::CursorPosition(3, 7),
+                Style::RESET,
+                bold.ansi_sequence(),
+                Style::RESET,
+                CSI::RestoreCursorPosition
+            )
+        );
+    }
+
+    #[test]
+    fn move_forward_over_the_unchanged_cells_of_a_row() {
+        let mut screen = screen();
+        let style = Style::new();
+
+        screen.draw_char(2, 1, 'a', &style);
+        screen.draw_char(5, 1, 'b', &style);
+        screen.present().unwrap();
+
+        let output = output(&mut screen);
+        assert!(
+            output.contains(&format!("a{}b", CSI::CursorForward(2))),
+            "{output:?}"
+        );
+    }
+
+    #[test]
+    fn never_write_on_the_terminal_when_drawing() {
+        let mut screen = screen();
+
+        screen.draw_str(1, 1, "apple", &Style::new());
+
+        assert_eq!(output(&mut screen), "");
     }
 }
@@ -0,0 +1,131 @@
+use std::{
+    io::{self, Write},
+    panic,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, Once,
+    },
+    thread,
+};
+
+use error_chain::error_chain;
+use signal_hook::{
+    consts::{SIGINT, SIGTERM},
+    iterator::Signals,
+    low_level,
+};
+
+use crate::terminus::ansi_escape_sequences::{CSI, SGR};
+
+error_chain! {
+    errors {
+        SessionAlreadyStarted {
+            description("Only one terminal session can be active at a time.")
+        }
+    }
+
+    foreign_links {
+        Io(std::io::Error);
+    }
+}
+
+/// True while a session own the terminal.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// The panic hook is installed by the first session only.
+static INSTALL_PANIC_HOOK: Once = Once::new();
+/// True once the signal handler is installed, a session try again when it failed before.
+static SIGNAL_HANDLER_INSTALLED: Mutex<bool> = Mutex::new(false);
+
+/// Put the terminal in the state needed by a full screen application.
+///
+/// Starting a session turn on raw mode, switch to the alternative screen buffer and hide the cursor.
+/// Everything is restored when the session is dropped, when the program panic
+/// and when the program receive SIGINT or SIGTERM.
+pub struct TerminalSession {
+    _private: (),
+}
+
+impl TerminalSession {
+    pub fn start() -> Result<TerminalSession> {
+        if ACTIVE.swap(true, Ordering::SeqCst) {
+            return Err(Error::from_kind(ErrorKind::SessionAlreadyStarted));
+        }
+        // Nothing was changed yet, so an error only frees the terminal for another session.
+        if let Err(error) = Self::install_handlers().and_then(|_| {
+            crossterm::terminal::enable_raw_mode()?;
+            Ok(())
+        }) {
+            ACTIVE.store(false, Ordering::SeqCst);
+            return Err(error);
+        }
+        let session = Self { _private: () };
+        let mut out = io::stdout();
+        out.write_all(CSI::EnableAlternativeScreenBuffer.to_string().as_bytes())?;
+        out.write_all(CSI::HideCursor.to_string().as_bytes())?;
+        out.flush()?;
+        Ok(session)
+    }
+
+    fn install_handlers() -> Result<()> {
+        INSTALL_PANIC_HOOK.call_once(install_panic_hook);
+        let mut is_installed = SIGNAL_HANDLER_INSTALLED
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        if !*is_installed {
+            install_signal_handler()?;
+            *is_installed = true;
+        }
+        Ok(())
+    }
+
+    /// Give the terminal back to the user.
+    ///
+    /// Does nothing if no session is active, so it is safe to call more than once.
+    pub fn restore() {
+        if !ACTIVE.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        let mut out = io::stdout();
+        let _ = out.write_all(
+            CSI::SelectGraphicRendition(SGR::Reset)
+                .to_string()
+                .as_bytes(),
+        );
+        let _ = out.write_all(CSI::ShowCursor.to_string().as_bytes());
+        let _ = out.write_all(CSI::DisableAlternativeScreenBuffer.to_string().as_bytes());
+        let _ = out.flush();
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        Self::restore();
+    }
+}
+
+/// Restore the terminal before the panic message is printed, otherwise it is lost
+/// in the alternative screen buffer.
+fn install_panic_hook() {
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        TerminalSession::restore();
+        previous_hook(info);
+    }));
+}
+
+/// Restore the terminal and exit on SIGINT and SIGTERM while a session is active,
+/// the signal does what it does by default otherwise.
+fn install_signal_handler() -> Result<()> {
+    let mut signals = Signals::new([SIGINT, SIGTERM])?;
+    thread::spawn(move || {
+        for signal in signals.forever() {
+            if ACTIVE.load(Ordering::SeqCst) {
+                TerminalSession::restore();
+                std::process::exit(128 + signal);
+            }
+            let _ = low_level::emulate_default_handler(signal);
+        }
+    });
+    Ok(())
+}
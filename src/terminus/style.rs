@@ -1,7 +1,9 @@
 use std::{
     collections::HashMap,
+    env,
     fmt::{Debug, Display, Formatter},
     hash::{Hash, Hasher},
+    sync::atomic::{AtomicU8, Ordering},
 };
 
 use crate::terminus::ansi_escape_sequences::{TerminalColor, CSI, SGR};
@@ -24,9 +26,274 @@ pub enum Color {
     BrightMagenta,
     BrightCyan,
     BrightWhite,
+    /// Color of the 256 colors palette.
+    ///
+    /// 0 to 15 are the named colors, 16 to 231 a 6x6x6 color cube and 232 to 255 a grayscale.
+    Indexed(u8),
     RGB(u8, u8, u8),
 }
 
+/// Named colors in the order of their index in the palette.
+const NAMED_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::Gray,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+/// Usual RGB value of the named colors, the real value depend on the terminal theme.
+const NAMED_COLORS_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Intensity of each level of the 6x6x6 color cube of the palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    /// Index of the color in the 256 colors palette, `None` for RGB colors.
+    pub fn index(&self) -> Option<u8> {
+        match self {
+            Self::Indexed(index) => Some(*index),
+            Self::RGB(..) => None,
+            named => NAMED_COLORS
+                .iter()
+                .position(|c| c == named)
+                .map(|i| i as u8),
+        }
+    }
+
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            Self::RGB(r, g, b) => (*r, *g, *b),
+            color => {
+                let index = color.index().unwrap_or_default();
+                match index {
+                    0..=15 => NAMED_COLORS_RGB[index as usize],
+                    16..=231 => {
+                        let i = index - 16;
+                        (
+                            CUBE_LEVELS[(i / 36) as usize],
+                            CUBE_LEVELS[(i / 6 % 6) as usize],
+                            CUBE_LEVELS[(i % 6) as usize],
+                        )
+                    }
+                    _ => {
+                        let level = 8 + 10 * (index - 232);
+                        (level, level, level)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Nearest color that can be displayed with the color depth.
+    ///
+    /// Return `None` when the terminal can't display colors.
+    pub fn downgrade(self, depth: ColorDepth) -> Option<Color> {
+        match (depth, self) {
+            (ColorDepth::Monochrome, _) => None,
+            (ColorDepth::TrueColor, color) => Some(color),
+            (ColorDepth::Ansi256, Self::RGB(r, g, b)) => Some(Self::nearest_indexed(r, g, b)),
+            (ColorDepth::Ansi256 | ColorDepth::Ansi16, Self::Indexed(index)) if index < 16 => {
+                Some(NAMED_COLORS[index as usize])
+            }
+            (ColorDepth::Ansi256, color) => Some(color),
+            (ColorDepth::Ansi16, Self::RGB(..) | Self::Indexed(_)) => Some(self.nearest_named()),
+            (ColorDepth::Ansi16, color) => Some(color),
+        }
+    }
+
+    /// Nearest color of the palette without the named colors, their value depend on the theme.
+    fn nearest_indexed(r: u8, g: u8, b: u8) -> Color {
+        let level = |c: u8| {
+            CUBE_LEVELS
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, l)| (**l as i32 - c as i32).abs())
+                .map(|(i, _)| i as u8)
+                .unwrap_or_default()
+        };
+        let cube = Self::Indexed(16 + 36 * level(r) + 6 * level(g) + level(b));
+        let average = (r as u16 + g as u16 + b as u16) / 3;
+        let gray = Self::Indexed(232 + (average.saturating_sub(3) / 10).min(23) as u8);
+
+        if distance(cube.rgb(), (r, g, b)) <= distance(gray.rgb(), (r, g, b)) {
+            cube
+        } else {
+            gray
+        }
+    }
+
+    /// Named color with the nearest hue.
+    ///
+    /// The distance between the RGB values is not used to find the hue since it turn every dark
+    /// color into gray, it only choose between the normal and the bright color of the hue.
+    fn nearest_named(&self) -> Color {
+        let (r, g, b) = self.rgb();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+
+        let nearest = |candidates: &[usize]| {
+            let index = candidates
+                .iter()
+                .copied()
+                .min_by_key(|index| distance(NAMED_COLORS_RGB[*index], (r, g, b)))
+                .unwrap_or_default();
+            NAMED_COLORS[index]
+        };
+
+        if max - min < 48 {
+            // Black, gray, white and bright white.
+            return nearest(&[0, 8, 7, 15]);
+        }
+        // Keep the components closer to the max than the min.
+        let threshold = (max as u16 + min as u16) / 2;
+        let on = |c: u8| (c as u16 > threshold) as usize;
+        let index = on(r) | on(g) << 1 | on(b) << 2;
+        nearest(&[index, index + 8])
+    }
+
+    fn foreground(&self) -> SGR {
+        match self {
+            Self::Indexed(index) => SGR::SetForegroundColorIndexed(*index),
+            Self::RGB(r, g, b) => SGR::SetForegroundColorRGB {
+                r: *r,
+                g: *g,
+                b: *b,
+            },
+            color => {
+                let index = color.index().unwrap_or_default();
+                if index < 8 {
+                    SGR::SetForegroundColor(TERMINAL_COLORS[index as usize])
+                } else {
+                    SGR::SetBrightForegroundColor(TERMINAL_COLORS[index as usize - 8])
+                }
+            }
+        }
+    }
+
+    fn background(&self) -> SGR {
+        match self {
+            Self::Indexed(index) => SGR::SetBackgroundColorIndexed(*index),
+            Self::RGB(r, g, b) => SGR::SetBackgroundColorRGB {
+                r: *r,
+                g: *g,
+                b: *b,
+            },
+            color => {
+                let index = color.index().unwrap_or_default();
+                if index < 8 {
+                    SGR::SetBackgroundColor(TERMINAL_COLORS[index as usize])
+                } else {
+                    SGR::SetBrightBackgroundColor(TERMINAL_COLORS[index as usize - 8])
+                }
+            }
+        }
+    }
+}
+
+const TERMINAL_COLORS: [TerminalColor; 8] = [
+    TerminalColor::Black,
+    TerminalColor::Red,
+    TerminalColor::Green,
+    TerminalColor::Yellow,
+    TerminalColor::Blue,
+    TerminalColor::Magenta,
+    TerminalColor::Cyan,
+    TerminalColor::White,
+];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// Number of colors the terminal can display.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    Monochrome,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/// Color depth in use, `UNDETECTED` until it is needed or set.
+static COLOR_DEPTH: AtomicU8 = AtomicU8::new(UNDETECTED);
+const UNDETECTED: u8 = u8::MAX;
+
+impl ColorDepth {
+    /// Detect the color depth with the `NO_COLOR`, `COLORTERM` and `TERM` environment variables.
+    pub fn detect() -> ColorDepth {
+        let var = |name| env::var(name).unwrap_or_default().to_lowercase();
+        let (no_color, colorterm, term) = (var("NO_COLOR"), var("COLORTERM"), var("TERM"));
+
+        if !no_color.is_empty() || term == "dumb" {
+            Self::Monochrome
+        } else if colorterm == "truecolor" || colorterm == "24bit" {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// Color depth used to display the styles, detected the first time it is needed.
+    pub fn current() -> ColorDepth {
+        match COLOR_DEPTH.load(Ordering::Relaxed) {
+            0 => Self::Monochrome,
+            1 => Self::Ansi16,
+            2 => Self::Ansi256,
+            3 => Self::TrueColor,
+            _ => {
+                let depth = Self::detect();
+                Self::set_current(depth);
+                depth
+            }
+        }
+    }
+
+    /// Use this color depth instead of the detected one.
+    ///
+    /// What is already displayed keep its colors until it is written again.
+    pub fn set_current(depth: ColorDepth) {
+        let value = match depth {
+            Self::Monochrome => 0,
+            Self::Ansi16 => 1,
+            Self::Ansi256 => 2,
+            Self::TrueColor => 3,
+        };
+        COLOR_DEPTH.store(value, Ordering::Relaxed);
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum StyleProperty {
     /// Set foreground color.
@@ -58,80 +325,42 @@ impl StyleProperty {
             StyleProperty::Invert => 9,
         }
     }
+
+    /// Graphic Rendition of the property with the colors of the depth,
+    /// none for a color the depth cannot display.
+    pub fn sgr(&self, depth: ColorDepth) -> Option<SGR> {
+        Some(match self {
+            StyleProperty::Color(color) => color.downgrade(depth)?.foreground(),
+            StyleProperty::BackgroundColor(color) => color.downgrade(depth)?.background(),
+            StyleProperty::Bold => SGR::Bold,
+            StyleProperty::Italic => SGR::Italic,
+            StyleProperty::Strike => SGR::Strike,
+            StyleProperty::Dim => SGR::Dim,
+            StyleProperty::Underline => SGR::Underline,
+            StyleProperty::DoublyUnderline => SGR::DoublyUnderlined,
+            StyleProperty::Blinking => SGR::SlowBlink,
+            StyleProperty::Hidden => SGR::Hide,
+            StyleProperty::Invert => SGR::Invert,
+        })
+    }
 }
 
 impl Hash for StyleProperty {
     fn hash<H: Hasher>(&self, state: &mut H) {
         state.write_u8(self.id());
-        state.finish();
     }
 }
 
 impl Display for StyleProperty {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(
-            &CSI::SelectGraphicRendition(match self {
-                StyleProperty::Color(color) => match color {
-                    Color::Black => SGR::SetForegroundColor(TerminalColor::Black),
-                    Color::Red => SGR::SetForegroundColor(TerminalColor::Red),
-                    Color::Green => SGR::SetForegroundColor(TerminalColor::Green),
-                    Color::Yellow => SGR::SetForegroundColor(TerminalColor::Yellow),
-                    Color::Blue => SGR::SetForegroundColor(TerminalColor::Blue),
-                    Color::Magenta => SGR::SetForegroundColor(TerminalColor::Magenta),
-                    Color::Cyan => SGR::SetForegroundColor(TerminalColor::Cyan),
-                    Color::White => SGR::SetForegroundColor(TerminalColor::White),
-                    Color::Gray => SGR::SetBrightForegroundColor(TerminalColor::Black),
-                    Color::BrightRed => SGR::SetBrightForegroundColor(TerminalColor::Red),
-                    Color::BrightGreen => SGR::SetBrightForegroundColor(TerminalColor::Green),
-                    Color::BrightYellow => SGR::SetBrightForegroundColor(TerminalColor::Yellow),
-                    Color::BrightBlue => SGR::SetBrightForegroundColor(TerminalColor::Blue),
-                    Color::BrightMagenta => SGR::SetBrightForegroundColor(TerminalColor::Magenta),
-                    Color::BrightCyan => SGR::SetBrightForegroundColor(TerminalColor::Cyan),
-                    Color::BrightWhite => SGR::SetBrightForegroundColor(TerminalColor::White),
-                    Color::RGB(r, g, b) => SGR::SetForegroundColorRGB {
-                        r: *r,
-                        g: *g,
-                        b: *b,
-                    },
-                },
-                StyleProperty::BackgroundColor(color) => match color {
-                    Color::Black => SGR::SetBackgroundColor(TerminalColor::Black),
-                    Color::Red => SGR::SetBackgroundColor(TerminalColor::Red),
-                    Color::Green => SGR::SetBackgroundColor(TerminalColor::Green),
-                    Color::Yellow => SGR::SetBackgroundColor(TerminalColor::Yellow),
-                    Color::Blue => SGR::SetBackgroundColor(TerminalColor::Blue),
-                    Color::Magenta => SGR::SetBackgroundColor(TerminalColor::Magenta),
-                    Color::Cyan => SGR::SetBackgroundColor(TerminalColor::Cyan),
-                    Color::White => SGR::SetBackgroundColor(TerminalColor::White),
-                    Color::Gray => SGR::SetBrightBackgroundColor(TerminalColor::Black),
-                    Color::BrightRed => SGR::SetBrightBackgroundColor(TerminalColor::Red),
-                    Color::BrightGreen => SGR::SetBrightBackgroundColor(TerminalColor::Green),
-                    Color::BrightYellow => SGR::SetBrightBackgroundColor(TerminalColor::Yellow),
-                    Color::BrightBlue => SGR::SetBrightBackgroundColor(TerminalColor::Blue),
-                    Color::BrightMagenta => SGR::SetBrightBackgroundColor(TerminalColor::Magenta),
-                    Color::BrightCyan => SGR::SetBrightBackgroundColor(TerminalColor::Cyan),
-                    Color::BrightWhite => SGR::SetBrightBackgroundColor(TerminalColor::White),
-                    Color::RGB(r, g, b) => SGR::SetBackgroundColorRGB {
-                        r: *r,
-                        g: *g,
-                        b: *b,
-                    },
-                },
-                StyleProperty::Bold => SGR::Bold,
-                StyleProperty::Italic => SGR::Italic,
-                StyleProperty::Strike => SGR::Strike,
-                StyleProperty::Dim => SGR::Dim,
-                StyleProperty::Underline => SGR::Underline,
-                StyleProperty::DoublyUnderline => SGR::DoublyUnderlined,
-                StyleProperty::Blinking => SGR::SlowBlink,
-                StyleProperty::Hidden => SGR::Hide,
-                StyleProperty::Invert => SGR::Invert,
-            })
-            .to_string(),
-        )
+        match self.sgr(ColorDepth::current()) {
+            Some(sgr) => f.write_str(&CSI::SelectGraphicRendition(sgr).to_string()),
+            None => Ok(()),
+        }
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Style {
     properties: HashMap<u8, StyleProperty>,
 }
@@ -176,13 +405,18 @@ impl Style {
     pub fn prettifier(&self) -> impl Fn(&str) -> String {
         let ansi_seq = self.ansi_sequence();
         let reset = CSI::SelectGraphicRendition(SGR::Reset).to_string();
-        return move |s: &str| format!("{ansi_seq}{s}{reset}");
+        move |s: &str| format!("{ansi_seq}{s}{reset}")
     }
 
     pub fn ansi_sequence(&self) -> String {
+        self.ansi_sequence_with(ColorDepth::current())
+    }
+
+    /// Sequence of the style with the colors of the depth instead of the current one.
+    pub fn ansi_sequence_with(&self, depth: ColorDepth) -> String {
         let mut sequence = String::new();
-        for style_prop in self.properties.values() {
-            sequence += style_prop.to_string().as_str();
+        for sgr in self.properties.values().filter_map(|p| p.sgr(depth)) {
+            sequence += CSI::SelectGraphicRendition(sgr).to_string().as_str();
         }
         sequence
     }
@@ -216,3 +450,62 @@ impl From<&Style> for Style {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, ColorDepth, NAMED_COLORS, NAMED_COLORS_RGB};
+
+    #[test]
+    fn downgrade_the_first_16_indexed_colors_to_their_named_color() {
+        for depth in [ColorDepth::Ansi16, ColorDepth::Ansi256] {
+            for (index, named) in NAMED_COLORS.iter().enumerate() {
+                assert_eq!(
+                    Color::Indexed(index as u8).downgrade(depth),
+                    Some(*named),
+                    "{depth:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn downgrade_to_the_nearest_named_color() {
+        assert_eq!(
+            Color::RGB(205, 0, 0).downgrade(ColorDepth::Ansi16),
+            Some(Color::Red)
+        );
+        assert_eq!(
+            Color::RGB(250, 10, 0).downgrade(ColorDepth::Ansi16),
+            Some(Color::BrightRed)
+        );
+        assert_eq!(
+            Color::RGB(120, 10, 10).downgrade(ColorDepth::Ansi16),
+            Some(Color::Red)
+        );
+        assert_eq!(
+            Color::Indexed(21).downgrade(ColorDepth::Ansi16),
+            Some(Color::Blue)
+        );
+        assert_eq!(
+            Color::RGB(100, 100, 250).downgrade(ColorDepth::Ansi16),
+            Some(Color::BrightBlue)
+        );
+    }
+
+    #[test]
+    fn downgrade_the_rgb_of_each_named_color_to_itself() {
+        for (named, (r, g, b)) in NAMED_COLORS.iter().zip(NAMED_COLORS_RGB) {
+            assert_eq!(
+                Color::RGB(r, g, b).downgrade(ColorDepth::Ansi16),
+                Some(*named)
+            );
+        }
+    }
+
+    #[test]
+    fn keep_colors_in_true_color_and_drop_them_in_monochrome() {
+        let color = Color::RGB(1, 2, 3);
+        assert_eq!(color.downgrade(ColorDepth::TrueColor), Some(color));
+        assert_eq!(color.downgrade(ColorDepth::Monochrome), None);
+    }
+}
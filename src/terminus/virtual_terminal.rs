@@ -0,0 +1,439 @@
+use std::{
+    io,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::terminus::{
+    ansi_escape_sequences::{ControlCharacters, EraseOption, TerminalColor, CSI, SGR},
+    buffer::{Buffer, Cell},
+    parser::{AnsiParser, Sequence},
+    style::{Color, Style, StyleProperty},
+};
+const TAB_WIDTH: u16 = 8;
+
+/// In memory terminal that interpret what is written in it.
+///
+/// It keep a grid of styled cells and the cursor state, so what a `Screen` output
+/// can be inspected without a real terminal.
+/// Cloning a virtual terminal give another handle on the same grid,
+/// which is what `Screen::new` need to create its output and its cursor.
+#[derive(Clone)]
+pub struct VirtualTerminal {
+    state: Arc<Mutex<VirtualTerminalState>>,
+}
+
+impl VirtualTerminal {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(VirtualTerminalState::new(width, height))),
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        self.state().grid.size()
+    }
+
+    /// Cell at the position x, y where 1, 1 is the top left corner.
+    pub fn cell(&self, x: u16, y: u16) -> Option<Cell> {
+        self.state().grid.get(x, y).cloned()
+    }
+
+    pub fn cursor_position(&self) -> (u16, u16) {
+        let state = self.state();
+        (state.x, state.y)
+    }
+
+    pub fn is_cursor_visible(&self) -> bool {
+        self.state().cursor_visible
+    }
+
+    pub fn is_alternative_screen(&self) -> bool {
+        self.state().alternative_screen
+    }
+
+    /// Sequences written that the terminal does not interpret, in the order they were written.
+    ///
+    /// A Graphic Rendition without effect on the style is kept as its `CSI`.
+    pub fn unknown_sequences(&self) -> Vec<Sequence> {
+        self.state().unknown.clone()
+    }
+
+    /// Style that will be applied to the next character written.
+    pub fn current_style(&self) -> Style {
+        Style::from(&self.state().style)
+    }
+
+    pub fn resize(&self, width: u16, height: u16) {
+        let mut state = self.state();
+        state.grid.resize(width, height);
+        state.x = state.x.min(width.max(1));
+        state.y = state.y.min(height.max(1));
+    }
+
+    /// Text displayed in the terminal, one line per row without trailing whitespace.
+    pub fn contents(&self) -> String {
+        let state = self.state();
+        let (width, height) = state.grid.size();
+        let mut contents = String::new();
+        for y in 1..=height {
+            let line: String = (1..=width)
+                .filter_map(|x| state.grid.get(x, y).map(Cell::symbol))
+                .collect();
+            contents += line.trim_end();
+            contents.push('\n');
+        }
+        contents
+    }
+
+    fn state(&self) -> MutexGuard<'_, VirtualTerminalState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl io::Write for VirtualTerminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.state().feed(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct VirtualTerminalState {
+    grid: Buffer,
+    /// Cursor column, 1-based.
+    x: u16,
+    /// Cursor row, 1-based.
+    y: u16,
+    saved_position: Option<(u16, u16)>,
+    cursor_visible: bool,
+    alternative_screen: bool,
+    style: Style,
+    parser: AnsiParser,
+    unknown: Vec<Sequence>,
+}
+
+impl VirtualTerminalState {
+    fn new(width: u16, height: u16) -> Self {
+        Self {
+            grid: Buffer::new(width, height),
+            x: 1,
+            y: 1,
+            saved_position: None,
+            cursor_visible: true,
+            alternative_screen: false,
+            style: Style::new(),
+            parser: AnsiParser::new(),
+            unknown: Vec::new(),
+        }
+    }
+
+    fn feed(&mut self, buf: &[u8]) {
+        for sequence in self.parser.feed(buf) {
+            match sequence {
+                Sequence::Text(text) => text.chars().for_each(|c| self.character(c)),
+                Sequence::Control(control) => self.control_character(control),
+                Sequence::CSI(csi) => self.control_sequence(csi),
+                Sequence::Unknown(_) => self.unknown.push(sequence),
+            }
+        }
+    }
+
+    fn control_sequence(&mut self, csi: CSI) {
+        let (width, height) = self.grid.size();
+        let max = |n: u16| n.max(1);
+        // The cursor stays on the grid, on its first cell when the grid is empty.
+        let column = |x: u16| x.clamp(1, width.max(1));
+        let row = |y: u16| y.clamp(1, height.max(1));
+
+        match csi {
+            CSI::CursorUp(n) => self.y = row(self.y.saturating_sub(max(n))),
+            CSI::CursorDown(n) => self.y = row(self.y.saturating_add(max(n))),
+            CSI::CursorForward(n) => self.x = column(self.x.saturating_add(max(n))),
+            CSI::CursorBack(n) => self.x = column(self.x.saturating_sub(max(n))),
+            CSI::CursorNextLine(n) => (self.x, self.y) = (1, row(self.y.saturating_add(max(n)))),
+            CSI::CursorPreviousLine(n) => {
+                (self.x, self.y) = (1, row(self.y.saturating_sub(max(n))))
+            }
+            CSI::CursorHorizontalAbsolute(n) => self.x = column(n),
+            CSI::CursorPosition(n, m) | CSI::HorizontalVerticalPosition(n, m) => {
+                self.y = row(n);
+                self.x = column(m);
+            }
+            CSI::EraseInDisplay(option) => self.erase_in_display(option),
+            CSI::EraseInLine(option) => self.erase_in_line(option),
+            CSI::ScrollUp(n) => self.grid.scroll_up(max(n)),
+            CSI::ScrollDown(n) => self.grid.scroll_down(max(n)),
+            CSI::SelectGraphicRendition(sgr) => self.select_graphic_rendition(sgr),
+            CSI::SaveCursorPosition => self.saved_position = Some((self.x, self.y)),
+            CSI::RestoreCursorPosition => {
+                (self.x, self.y) = self.saved_position.unwrap_or((1, 1));
+            }
+            CSI::HideCursor => self.cursor_visible = false,
+            CSI::ShowCursor => self.cursor_visible = true,
+            CSI::EnableAlternativeScreenBuffer => self.alternative_screen = true,
+            CSI::DisableAlternativeScreenBuffer => self.alternative_screen = false,
+            CSI::AuxPortOn
+            | CSI::AuxPortOff
+            | CSI::DeviceStatusReport
+            | CSI::CursorPositionReport(_, _) => {}
+        }
+    }
+
+    fn erase_in_display(&mut self, option: EraseOption) {
+        let (width, height) = self.grid.size();
+        match option {
+            EraseOption::CursorToEnd => {
+                self.erase_in_line(option);
+                for y in self.y + 1..=height {
+                    self.erase_cells(1..=width, y);
+                }
+            }
+            EraseOption::CursorToBeginning => {
+                for y in 1..self.y {
+                    self.erase_cells(1..=width, y);
+                }
+                self.erase_in_line(option);
+            }
+            EraseOption::All => self.grid.clear(),
+        }
+    }
+
+    fn erase_in_line(&mut self, option: EraseOption) {
+        let width = self.grid.size().0;
+        match option {
+            EraseOption::CursorToEnd => self.erase_cells(self.x..=width, self.y),
+            EraseOption::CursorToBeginning => self.erase_cells(1..=self.x, self.y),
+            EraseOption::All => self.erase_cells(1..=width, self.y),
+        }
+    }
+
+    fn erase_cells(&mut self, columns: impl Iterator<Item = u16>, y: u16) {
+        for x in columns {
+            self.grid.set_char(x, y, ' ', &Style::new());
+        }
+    }
+
+    fn select_graphic_rendition(&mut self, sgr: SGR) {
+        let style = &mut self.style;
+        match sgr {
+            SGR::Reset => *style = Style::new(),
+            SGR::Bold => {
+                style.add_property(StyleProperty::Bold);
+            }
+            SGR::Dim => {
+                style.add_property(StyleProperty::Dim);
+            }
+            SGR::Italic => {
+                style.add_property(StyleProperty::Italic);
+            }
+            SGR::Underline => {
+                style.add_property(StyleProperty::Underline);
+            }
+            SGR::SlowBlink | SGR::RapidBlink => {
+                style.add_property(StyleProperty::Blinking);
+            }
+            SGR::Invert => {
+                style.add_property(StyleProperty::Invert);
+            }
+            SGR::Hide => {
+                style.add_property(StyleProperty::Hidden);
+            }
+            SGR::Strike => {
+                style.add_property(StyleProperty::Strike);
+            }
+            SGR::DoublyUnderlined => {
+                style.add_property(StyleProperty::DoublyUnderline);
+            }
+            SGR::NormalIntensity => {
+                style.remove_properties(&[StyleProperty::Bold, StyleProperty::Dim])
+            }
+            SGR::NeitherItalicNorBlackletter => style.remove_property(StyleProperty::Italic),
+            SGR::NotUnderlined => style.remove_property(StyleProperty::Underline),
+            SGR::NotBlinking => style.remove_property(StyleProperty::Blinking),
+            SGR::NotReversed => style.remove_property(StyleProperty::Invert),
+            SGR::Reveal => style.remove_property(StyleProperty::Hidden),
+            SGR::NotCrossedOut => style.remove_property(StyleProperty::Strike),
+            SGR::SetForegroundColor(color) => {
+                style.add_property(StyleProperty::Color(named_color(color, false)));
+            }
+            SGR::SetBrightForegroundColor(color) => {
+                style.add_property(StyleProperty::Color(named_color(color, true)));
+            }
+            SGR::SetForegroundColorRGB { r, g, b } => {
+                style.add_property(StyleProperty::Color(Color::RGB(r, g, b)));
+            }
+            SGR::SetForegroundColorIndexed(index) => {
+                style.add_property(StyleProperty::Color(Color::Indexed(index)));
+            }
+            SGR::DefaultForegroundColor => {
+                style.remove_property(StyleProperty::Color(Color::White))
+            }
+            SGR::SetBackgroundColor(color) => {
+                style.add_property(StyleProperty::BackgroundColor(named_color(color, false)));
+            }
+            SGR::SetBrightBackgroundColor(color) => {
+                style.add_property(StyleProperty::BackgroundColor(named_color(color, true)));
+            }
+            SGR::SetBackgroundColorRGB { r, g, b } => {
+                style.add_property(StyleProperty::BackgroundColor(Color::RGB(r, g, b)));
+            }
+            SGR::SetBackgroundColorIndexed(index) => {
+                style.add_property(StyleProperty::BackgroundColor(Color::Indexed(index)));
+            }
+            SGR::DefaultBackgroundColor => {
+                style.remove_property(StyleProperty::BackgroundColor(Color::Black))
+            }
+            _ => self
+                .unknown
+                .push(Sequence::CSI(CSI::SelectGraphicRendition(sgr))),
+        }
+    }
+
+    fn control_character(&mut self, control: ControlCharacters) {
+        let (width, height) = self.grid.size();
+        match control {
+            ControlCharacters::Backspace => self.x = self.x.saturating_sub(1).max(1),
+            ControlCharacters::Tab => {
+                let next_stop = self.x.saturating_sub(1) / TAB_WIDTH * TAB_WIDTH + TAB_WIDTH;
+                self.x = next_stop.saturating_add(1).min(width.max(1))
+            }
+            // The terminal translate line feed and form feed to a new line.
+            ControlCharacters::LineFeed | ControlCharacters::FormFeed => {
+                self.x = 1;
+                if self.y >= height {
+                    self.grid.scroll_up(1);
+                } else {
+                    self.y += 1;
+                }
+            }
+            ControlCharacters::CarriageReturn => self.x = 1,
+            ControlCharacters::Bell | ControlCharacters::Escape => {}
+        }
+    }
+
+    fn character(&mut self, c: char) {
+        let (width, height) = self.grid.size();
+        if self.x > width {
+            self.x = 1;
+            if self.y >= height {
+                self.grid.scroll_up(1);
+            } else {
+                self.y += 1;
+            }
+        }
+        self.grid.set_char(self.x, self.y, c, &self.style);
+        self.x += 1;
+    }
+}
+
+fn named_color(color: TerminalColor, bright: bool) -> Color {
+    match (color, bright) {
+        (TerminalColor::Black, false) => Color::Black,
+        (TerminalColor::Red, false) => Color::Red,
+        (TerminalColor::Green, false) => Color::Green,
+        (TerminalColor::Yellow, false) => Color::Yellow,
+        (TerminalColor::Blue, false) => Color::Blue,
+        (TerminalColor::Magenta, false) => Color::Magenta,
+        (TerminalColor::Cyan, false) => Color::Cyan,
+        (TerminalColor::White, false) => Color::White,
+        (TerminalColor::Black, true) => Color::Gray,
+        (TerminalColor::Red, true) => Color::BrightRed,
+        (TerminalColor::Green, true) => Color::BrightGreen,
+        (TerminalColor::Yellow, true) => Color::BrightYellow,
+        (TerminalColor::Blue, true) => Color::BrightBlue,
+        (TerminalColor::Magenta, true) => Color::BrightMagenta,
+        (TerminalColor::Cyan, true) => Color::BrightCyan,
+        (TerminalColor::White, true) => Color::BrightWhite,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::VirtualTerminal;
+    use crate::terminus::{
+        ansi_escape_sequences::{CSI, SGR},
+        parser::Sequence,
+        style::{Color, Style, StyleProperty},
+    };
+
+    fn terminal_with(width: u16, height: u16, output: &str) -> VirtualTerminal {
+        let mut terminal = VirtualTerminal::new(width, height);
+        terminal.write_all(output.as_bytes()).unwrap();
+        terminal
+    }
+
+    #[test]
+    fn write_text_at_the_cursor_position() {
+        let terminal = terminal_with(6, 3, "\x1b[2;3Hab\x1b[3;1Hc");
+        assert_eq!(terminal.contents(), "\n  ab\nc\n");
+        assert_eq!(terminal.cursor_position(), (2, 3));
+    }
+
+    #[test]
+    fn keep_the_style_of_each_cell() {
+        let terminal = terminal_with(4, 1, "\x1b[1m\x1b[38;2;1;2;3ma\x1b[0mb");
+        assert_eq!(
+            terminal.cell(1, 1).unwrap().style(),
+            &Style::from([
+                StyleProperty::Bold,
+                StyleProperty::Color(Color::RGB(1, 2, 3))
+            ])
+        );
+        assert_eq!(terminal.cell(2, 1).unwrap().style(), &Style::new());
+    }
+
+    #[test]
+    fn erase_from_the_cursor_to_the_end_of_the_line() {
+        let terminal = terminal_with(5, 2, "abcde\x1b[1;3H\x1b[0K");
+        assert_eq!(terminal.contents(), "ab\n\n");
+    }
+
+    #[test]
+    fn restore_the_saved_cursor_position() {
+        let terminal = terminal_with(5, 5, "\x1b[2;4H\x1b[s\x1b[5;5H\x1b[u");
+        assert_eq!(terminal.cursor_position(), (4, 2));
+    }
+
+    #[test]
+    fn keep_the_cursor_on_the_grid() {
+        let terminal = terminal_with(5, 4, "\x1b[65535B\x1b[65535C");
+        assert_eq!(terminal.cursor_position(), (5, 4));
+        let terminal = terminal_with(5, 4, "\x1b[3;3H\x1b[65535E");
+        assert_eq!(terminal.cursor_position(), (1, 4));
+        let terminal = terminal_with(5, 4, "\x1b[65535A\x1b[65535D");
+        assert_eq!(terminal.cursor_position(), (1, 1));
+    }
+
+    #[test]
+    fn move_to_the_next_tab_stop() {
+        let terminal = terminal_with(20, 1, "a\t");
+        assert_eq!(terminal.cursor_position(), (9, 1));
+        let terminal = terminal_with(0, 0, "\t");
+        assert_eq!(terminal.cursor_position(), (1, 1));
+    }
+
+    #[test]
+    fn record_the_sequences_it_does_not_interpret() {
+        let terminal = terminal_with(5, 1, "\x1b[1;51ma\x1b]0;title\x07\x1b[53mb");
+        assert_eq!(terminal.contents(), "ab\n");
+        assert_eq!(
+            terminal.unknown_sequences(),
+            vec![
+                Sequence::CSI(CSI::SelectGraphicRendition(SGR::Framed)),
+                Sequence::Unknown(b"\x1b]0;title\x07".to_vec()),
+                Sequence::CSI(CSI::SelectGraphicRendition(SGR::Overlined)),
+            ]
+        );
+        assert_eq!(
+            terminal.cell(1, 1).unwrap().style(),
+            &Style::from([StyleProperty::Bold])
+        );
+    }
+}
@@ -0,0 +1,486 @@
+use std::{fmt::Write as _, fs, path::Path, sync::Arc, thread};
+
+use clap::ValueEnum;
+use error_chain::error_chain;
+use serde_json::{json, Value};
+
+use crate::{
+    bot::{Bot, BotRegistry},
+    cli::{Cli, TournamentArgs},
+    component::{Boundary, Dimension, Position},
+    computer::Skill,
+    controller,
+    game_state::{fruit::FruitRates, DeathCause, GameState, Player},
+    map::Map,
+    settings::{GameMode, Opponent},
+};
+
+error_chain! {
+    errors {
+        BoardTooSmall {
+            description("The board is too small to hold the snake.")
+            display("The board is too small to hold the snake.")
+        }
+        UnknownBot(name: String) {
+            description("No bot has this name.")
+            display("No bot is named '{}'.", name)
+        }
+        UnplayableBot(bot: &'static str) {
+            description("The bot cannot play on the board of the tournament.")
+            display("The {} bot cannot play on the board of the tournament.", bot)
+        }
+    }
+
+    foreign_links {
+        Io(std::io::Error);
+    }
+}
+
+/// Format of the file the results are written to.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+pub enum ResultFormat {
+    /// One line per round.
+    #[default]
+    Csv,
+    /// The ranking of the bots with their rounds.
+    Json,
+}
+
+/// How a round ended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Died(DeathCause),
+    /// The snake filled the board.
+    Won,
+    /// The round was stopped after the maximum number of steps.
+    OutOfTime,
+}
+
+impl Outcome {
+    /// Every way a solo round can end, the other snake is never there to be hit.
+    const ALL: [Outcome; 4] = [
+        Outcome::Died(DeathCause::Wall),
+        Outcome::Died(DeathCause::Body),
+        Outcome::Won,
+        Outcome::OutOfTime,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Died(cause) => cause.name(),
+            Outcome::Won => "won",
+            Outcome::OutOfTime => "out of time",
+        }
+    }
+}
+
+/// Rules shared by every round of the tournament.
+pub struct Tournament {
+    pub rounds: u64,
+    /// Seed of the first round, each next round use the next seed.
+    pub first_seed: u64,
+    pub board: Dimension,
+    pub mode: GameMode,
+    pub map: Option<Arc<Map>>,
+    pub fruit_rates: FruitRates,
+    /// Bots which can enter, the `computer` one plays with the skill given on the command line.
+    pub registry: BotRegistry,
+    pub max_ticks: u64,
+}
+
+/// End of a round played by a bot.
+#[derive(Debug, Copy, Clone)]
+pub struct RoundResult {
+    pub seed: u64,
+    pub score: u32,
+    pub length: usize,
+    pub ticks: u64,
+    pub outcome: Outcome,
+}
+
+/// Every round played by a bot.
+pub struct Standing {
+    pub bot: &'static str,
+    pub results: Vec<RoundResult>,
+}
+
+impl Tournament {
+    /// Rounds set up from the options of the command line.
+    pub fn new(cli: &Cli, args: &TournamentArgs, map: Option<Map>) -> Self {
+        Self {
+            rounds: args.rounds,
+            first_seed: cli.seed.unwrap_or_default(),
+            board: Dimension::new(args.width, args.height),
+            mode: cli.mode,
+            map: map.map(Arc::new),
+            fruit_rates: FruitRates {
+                golden: cli.golden_rate,
+                shrink: cli.shrink_rate,
+                slow_motion: cli.slow_motion_rate,
+                poison: cli.poison_rate,
+            },
+            registry: BotRegistry::new(Skill {
+                reaction_delay: cli.computer_delay,
+                mistake_rate: cli.computer_mistakes,
+            }),
+            max_ticks: args.max_ticks,
+        }
+    }
+
+    /// Bots with the names, or every bot which can play on the board when none is given.
+    ///
+    /// A board too small for the snake, and a bot which cannot play every round, are refused.
+    pub fn bots(&self, names: &[String]) -> Result<Vec<&Bot>> {
+        let state = self.state(self.first_seed);
+        if !state.can_hold_snake() {
+            return Err(ErrorKind::BoardTooSmall.into());
+        }
+        if names.is_empty() {
+            return Ok(self
+                .registry
+                .bots()
+                .iter()
+                .filter(|bot| bot.can_play(&state))
+                .collect());
+        }
+        names
+            .iter()
+            .map(|name| {
+                let bot = self
+                    .registry
+                    .get(name)
+                    .ok_or_else(|| ErrorKind::UnknownBot(name.clone()))?;
+                if !bot.can_play(&state) {
+                    return Err(ErrorKind::UnplayableBot(bot.name()).into());
+                }
+                Ok(bot)
+            })
+            .collect()
+    }
+
+    /// Play the rounds of each bot, the bots play at the same time on their own thread.
+    ///
+    /// The standings are ranked from the best mean score.
+    pub fn run(&self, bots: &[&Bot]) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = thread::scope(|scope| {
+            let handles: Vec<_> = bots
+                .iter()
+                .map(|bot| scope.spawn(move || self.standing(bot)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .expect("A bot panicked during the tournament.")
+                })
+                .collect()
+        });
+        rank(&mut standings);
+        standings
+    }
+
+    fn standing(&self, bot: &Bot) -> Standing {
+        let results = (0..self.rounds)
+            .map(|round| self.play(bot, self.first_seed.wrapping_add(round)))
+            .collect();
+        Standing {
+            bot: bot.name(),
+            results,
+        }
+    }
+
+    /// Start of a round, the board is at the top left of a screen which is never drawn.
+    fn state(&self, seed: u64) -> GameState {
+        GameState::new(
+            Boundary::new(Position::new(0, 0), self.board),
+            seed,
+            self.mode,
+            Opponent::Solo,
+            self.map.clone(),
+            self.fruit_rates,
+        )
+    }
+
+    /// Play a round as fast as possible, without drawing it.
+    fn play(&self, bot: &Bot, seed: u64) -> RoundResult {
+        let mut state = self.state(seed);
+        let mut controllers = vec![(Player::One, bot.controller(&state))];
+        while !state.is_over() && state.tick() < self.max_ticks {
+            let turns = controller::turns(&mut controllers, &state);
+            state.step(&turns);
+        }
+        let outcome = match state.death_cause() {
+            Some(cause) => Outcome::Died(cause),
+            None if state.is_won() => Outcome::Won,
+            None => Outcome::OutOfTime,
+        };
+        RoundResult {
+            seed,
+            score: state.score(),
+            length: state.snake().nodes().len(),
+            ticks: state.tick(),
+            outcome,
+        }
+    }
+}
+
+impl Standing {
+    pub fn mean(&self, value: impl Fn(&RoundResult) -> f64) -> f64 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+        self.results.iter().map(value).sum::<f64>() / self.results.len() as f64
+    }
+
+    pub fn median(&self, value: impl Fn(&RoundResult) -> f64) -> f64 {
+        let mut values: Vec<f64> = self.results.iter().map(value).collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+        match values.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (values[len / 2 - 1] + values[len / 2]) / 2.0,
+            len => values[len / 2],
+        }
+    }
+
+    /// Number of rounds which ended this way.
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.outcome == outcome)
+            .count()
+    }
+}
+
+/// Sort the standings from the best mean score, then from the best mean length.
+///
+/// The standings still tied keep their order.
+fn rank(standings: &mut [Standing]) {
+    standings.sort_by(|a, b| {
+        let key = |standing: &Standing| {
+            (
+                standing.mean(|result| result.score as f64),
+                standing.mean(|result| result.length as f64),
+            )
+        };
+        key(b)
+            .partial_cmp(&key(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+/// Ranking table of the standings, with the mean and median of each round result.
+pub fn ranking_table(standings: &[Standing]) -> String {
+    let mut table = format!(
+        "{:<4}  {:<10}  {:>19}  {:>19}  {:>19}",
+        "Rank", "Bot", "Score mean/median", "Length mean/median", "Ticks mean/median"
+    );
+    for outcome in Outcome::ALL {
+        let _ = write!(table, "  {:>11}", outcome.name());
+    }
+    table.push('\n');
+    for (rank, standing) in standings.iter().enumerate() {
+        let statistic = |value: fn(&RoundResult) -> f64| {
+            format!(
+                "{:.1} / {:.1}",
+                standing.mean(value),
+                standing.median(value)
+            )
+        };
+        let _ = write!(
+            table,
+            "{:<4}  {:<10}  {:>19}  {:>19}  {:>19}",
+            rank + 1,
+            standing.bot,
+            statistic(|result| result.score as f64),
+            statistic(|result| result.length as f64),
+            statistic(|result| result.ticks as f64),
+        );
+        for outcome in Outcome::ALL {
+            let _ = write!(table, "  {:>11}", standing.count(outcome));
+        }
+        table.push('\n');
+    }
+    table
+}
+
+/// Write the result of every round to the file.
+pub fn save(standings: &[Standing], path: impl AsRef<Path>, format: ResultFormat) -> Result<()> {
+    let content = match format {
+        ResultFormat::Csv => to_csv(standings),
+        ResultFormat::Json => to_json(standings),
+    };
+    Ok(fs::write(path, content)?)
+}
+
+fn to_csv(standings: &[Standing]) -> String {
+    let mut csv = String::from("bot,seed,score,length,ticks,outcome\n");
+    for standing in standings {
+        for result in &standing.results {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                standing.bot,
+                result.seed,
+                result.score,
+                result.length,
+                result.ticks,
+                result.outcome.name()
+            );
+        }
+    }
+    csv
+}
+
+fn to_json(standings: &[Standing]) -> String {
+    let statistic = |standing: &Standing, value: fn(&RoundResult) -> f64| json!({ "mean": standing.mean(value), "median": standing.median(value) });
+    let standings: Vec<Value> = standings
+        .iter()
+        .enumerate()
+        .map(|(rank, standing)| {
+            let outcomes: serde_json::Map<String, Value> = Outcome::ALL
+                .iter()
+                .map(|outcome| (outcome.name().to_string(), standing.count(*outcome).into()))
+                .collect();
+            let results: Vec<Value> = standing
+                .results
+                .iter()
+                .map(|result| {
+                    json!({
+                        "seed": result.seed,
+                        "score": result.score,
+                        "length": result.length,
+                        "ticks": result.ticks,
+                        "outcome": result.outcome.name(),
+                    })
+                })
+                .collect();
+            json!({
+                "rank": rank + 1,
+                "bot": standing.bot,
+                "rounds": standing.results.len(),
+                "score": statistic(standing, |result| result.score as f64),
+                "length": statistic(standing, |result| result.length as f64),
+                "ticks": statistic(standing, |result| result.ticks as f64),
+                "outcomes": outcomes,
+                "results": results,
+            })
+        })
+        .collect();
+    format!("{:#}\n", Value::Array(standings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(seed: u64, score: u32, length: usize, outcome: Outcome) -> RoundResult {
+        RoundResult {
+            seed,
+            score,
+            length,
+            ticks: seed * 10,
+            outcome,
+        }
+    }
+
+    /// Standing whose rounds have the scores, all died on a wall with a length of 11.
+    fn standing(bot: &'static str, scores: &[u32]) -> Standing {
+        Standing {
+            bot,
+            results: scores
+                .iter()
+                .enumerate()
+                .map(|(seed, score)| {
+                    result(seed as u64, *score, 11, Outcome::Died(DeathCause::Wall))
+                })
+                .collect(),
+        }
+    }
+
+    fn score(result: &RoundResult) -> f64 {
+        result.score as f64
+    }
+
+    #[test]
+    fn take_the_middle_score_as_median() {
+        assert_eq!(standing("odd", &[300, 100, 200]).median(score), 200.0);
+        assert_eq!(standing("even", &[400, 100, 300, 200]).median(score), 250.0);
+    }
+
+    #[test]
+    fn give_zero_to_a_standing_without_rounds() {
+        let standing = standing("empty", &[]);
+        assert_eq!(standing.mean(score), 0.0);
+        assert_eq!(standing.median(score), 0.0);
+        assert_eq!(standing.count(Outcome::Won), 0);
+    }
+
+    #[test]
+    fn rank_from_the_best_mean_score_then_length() {
+        let mut longer = standing("longer", &[100, 300]);
+        longer.results[0].length = 20;
+        let mut standings = vec![
+            standing("first tied", &[200, 200]),
+            standing("best", &[500]),
+            longer,
+            standing("second tied", &[100, 300]),
+            standing("empty", &[]),
+        ];
+
+        rank(&mut standings);
+
+        let bots: Vec<&str> = standings.iter().map(|standing| standing.bot).collect();
+        assert_eq!(
+            bots,
+            ["best", "longer", "first tied", "second tied", "empty"]
+        );
+    }
+
+    #[test]
+    fn write_a_row_per_bot_in_the_ranking_table() {
+        let table = ranking_table(&[standing("greedy", &[100, 300])]);
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Rank  Bot "));
+        assert!(lines[0].ends_with("out of time"));
+        let row: Vec<&str> = lines[1].split_whitespace().collect();
+        assert_eq!(
+            row,
+            [
+                "1", "greedy", "200.0", "/", "200.0", "11.0", "/", "11.0", "5.0", "/", "5.0", "2",
+                "0", "0", "0"
+            ]
+        );
+    }
+
+    #[test]
+    fn write_a_csv_line_per_round() {
+        let mut standing = standing("cycle", &[]);
+        standing.results.push(result(7, 4200, 48, Outcome::Won));
+
+        assert_eq!(
+            to_csv(&[standing]),
+            "bot,seed,score,length,ticks,outcome\ncycle,7,4200,48,70,won\n"
+        );
+    }
+
+    #[test]
+    fn write_the_ranking_with_the_rounds_in_json() {
+        let standings = [standing("greedy", &[100, 300])];
+
+        let json: Value = serde_json::from_str(&to_json(&standings)).unwrap();
+
+        assert_eq!(json[0]["rank"], 1);
+        assert_eq!(json[0]["bot"], "greedy");
+        assert_eq!(json[0]["rounds"], 2);
+        assert_eq!(json[0]["score"], json!({ "mean": 200.0, "median": 200.0 }));
+        assert_eq!(json[0]["outcomes"]["wall"], 2);
+        assert_eq!(json[0]["outcomes"]["won"], 0);
+        assert_eq!(
+            json[0]["results"][1],
+            json!({ "seed": 1, "score": 300, "length": 11, "ticks": 10, "outcome": "wall" })
+        );
+    }
+}